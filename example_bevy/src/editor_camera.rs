@@ -0,0 +1,146 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use crate::editor_plugin::EditorMarker;
+
+/// How far the camera can look up or down, just short of straight up so yaw stays meaningful.
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+/// Pixels a line of scrolling is worth, for touchpads that scroll by the pixel.
+const PIXELS_PER_LINE: f32 = 16.0;
+/// How much closer to the focus a line of scrolling gets.
+const ZOOM_PER_LINE: f32 = 0.9;
+const MIN_DISTANCE: f32 = 0.1;
+/// Flying faster while shift is held.
+const SHIFT_SPEEDUP: f32 = 3.0;
+
+/// The camera the scene is looked at with while editing. Holding the right mouse button looks
+/// around and flies with WASD, Q and E, holding the middle one orbits around `focus` and scrolling
+/// zooms in on it.
+#[derive(Component, Debug, Clone)]
+pub struct EditorCamera {
+    /// what's orbited around and zoomed in on, kept in front of the camera while flying
+    pub focus: Vec3,
+    /// units per second
+    pub speed: f32,
+    /// radians per logical pixel the cursor moved
+    pub sensitivity: f32,
+}
+
+impl Default for EditorCamera {
+    fn default() -> Self {
+        Self {
+            focus: Vec3::ZERO,
+            speed: 5.0,
+            sensitivity: 0.005,
+        }
+    }
+}
+
+/// Set on the game's cameras that were switched off, so the editor camera is the only one
+/// rendering while editing.
+#[derive(Component)]
+pub(crate) struct DeactivatedCamera;
+
+/// The game's cameras render on top of the editor camera otherwise, also catches the ones a scene
+/// spawned.
+pub(crate) fn deactivate_game_cameras(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, &mut Camera), (Without<EditorMarker>, Without<DeactivatedCamera>)>,
+) {
+    for (entity, mut camera) in &mut cameras {
+        if camera.is_active {
+            camera.is_active = false;
+            commands.entity(entity).insert(DeactivatedCamera);
+        }
+    }
+}
+
+/// Switches the cameras [`deactivate_game_cameras`] switched off back on for playing.
+pub(crate) fn activate_game_cameras(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, &mut Camera), With<DeactivatedCamera>>,
+) {
+    for (entity, mut camera) in &mut cameras {
+        camera.is_active = true;
+        commands.entity(entity).remove::<DeactivatedCamera>();
+    }
+}
+
+/// Moves the [`EditorCamera`] with the input the editor forwarded. There's no raw mouse motion
+/// for it, so the cursor's movement is used instead.
+pub(crate) fn move_editor_camera(
+    time: Res<Time<Real>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut last_cursor_position: Local<Option<Vec2>>,
+    mut cameras: Query<(&mut Transform, &mut EditorCamera)>,
+) {
+    let mut cursor_delta = Vec2::ZERO;
+    for event in cursor_moved.read() {
+        if let Some(last) = *last_cursor_position {
+            cursor_delta += event.position - last;
+        }
+        *last_cursor_position = Some(event.position);
+    }
+    let scrolled = mouse_wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        })
+        .sum::<f32>();
+
+    let flying = mouse_buttons.pressed(MouseButton::Right);
+    let orbiting = !flying && mouse_buttons.pressed(MouseButton::Middle);
+
+    for (mut transform, mut camera) in &mut cameras {
+        let mut distance = transform
+            .translation
+            .distance(camera.focus)
+            .max(MIN_DISTANCE);
+
+        if flying || orbiting {
+            let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+            let yaw = yaw - cursor_delta.x * camera.sensitivity;
+            let pitch = (pitch - cursor_delta.y * camera.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+            transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+        }
+        let forward = transform.rotation * Vec3::NEG_Z;
+
+        if flying {
+            let right = transform.rotation * Vec3::X;
+            let mut direction = Vec3::ZERO;
+            for (key, key_direction) in [
+                (KeyCode::KeyW, forward),
+                (KeyCode::KeyS, -forward),
+                (KeyCode::KeyD, right),
+                (KeyCode::KeyA, -right),
+                (KeyCode::KeyE, Vec3::Y),
+                (KeyCode::KeyQ, Vec3::NEG_Y),
+            ] {
+                if keys.pressed(key) {
+                    direction += key_direction;
+                }
+            }
+            let mut speed = camera.speed;
+            if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                speed *= SHIFT_SPEEDUP;
+            }
+            transform.translation += direction.normalize_or_zero() * speed * time.delta_seconds();
+            camera.focus = transform.translation + forward * distance;
+        }
+
+        if scrolled != 0.0 {
+            distance = (distance * ZOOM_PER_LINE.powf(scrolled)).max(MIN_DISTANCE);
+        }
+        if orbiting || scrolled != 0.0 {
+            transform.translation = camera.focus - forward * distance;
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Instant;
+
+use bevy::{
+    asset::ReflectAsset,
+    diagnostic::{Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore},
+    prelude::*,
+};
+use roth_shared::DiagnosticValue;
+
+/// Adds an `assets/<type>` diagnostic for every reflected asset type, measuring how many of them
+/// are loaded.
+pub(crate) fn measure_asset_counts(world: &mut World) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let counts = type_registry
+        .iter()
+        .filter_map(|registration| {
+            let reflect_asset = registration.data::<ReflectAsset>()?;
+            // `ReflectAsset` panics when the `Assets<T>` resource doesn't exist
+            let assets_id = world
+                .components()
+                .get_resource_id(reflect_asset.assets_resource_type_id())?;
+            world.get_resource_by_id(assets_id)?;
+
+            let short_path = registration.type_info().type_path_table().short_path();
+            Some((format!("assets/{}", short_path), reflect_asset.len(world)))
+        })
+        .collect::<Vec<_>>();
+
+    let now = Instant::now();
+    let mut store = world.resource_mut::<DiagnosticsStore>();
+    for (path, count) in counts {
+        let path = DiagnosticPath::new(path);
+        if store.get(&path).is_none() {
+            store.add(Diagnostic::new(path.clone()).with_smoothing_factor(0.0));
+        }
+        store
+            .get_mut(&path)
+            .unwrap()
+            .add_measurement(DiagnosticMeasurement {
+                time: now,
+                value: count as f64,
+            });
+    }
+}
+
+/// Every enabled diagnostic that was measured at least once.
+pub(crate) fn diagnostic_values(store: &DiagnosticsStore) -> Vec<DiagnosticValue> {
+    let mut values = store
+        .iter()
+        .filter(|diagnostic| diagnostic.is_enabled)
+        .filter_map(|diagnostic| {
+            Some(DiagnosticValue {
+                path: diagnostic.path().to_string(),
+                value: diagnostic.smoothed()?,
+                suffix: diagnostic.suffix.to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+    values.sort_by(|a, b| a.path.cmp(&b.path));
+    values
+}
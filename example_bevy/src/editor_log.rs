@@ -0,0 +1,108 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+use bevy::{
+    log::{
+        tracing_subscriber::{
+            layer::{Context, SubscriberExt},
+            Layer,
+        },
+        BoxedSubscriber,
+    },
+    utils::tracing::{
+        field::{Field, Visit},
+        Event, Level, Subscriber,
+    },
+};
+use roth_shared::{LogLevel, RuntimeToEditorMsg};
+
+/// Records that weren't sent yet are kept up to this many, the oldest are dropped first, e.g.
+/// while no editor is attached.
+const MAX_QUEUED_RECORDS: usize = 1000;
+
+static QUEUE: Mutex<VecDeque<RuntimeToEditorMsg>> = Mutex::new(VecDeque::new());
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Forwards every log record to the editor, install it as bevy's
+/// `LogPlugin::update_subscriber`. The `EditorPlugin` sends the records once per frame.
+pub fn forward_logs(subscriber: BoxedSubscriber) -> BoxedSubscriber {
+    INSTALLED.store(true, Ordering::Relaxed);
+    Box::new(subscriber.with(EditorLogLayer))
+}
+
+/// Whether [`forward_logs`] was installed.
+pub(crate) fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// `Log` messages for everything logged since the last call.
+pub(crate) fn take_records() -> Vec<RuntimeToEditorMsg> {
+    QUEUE.lock().unwrap().drain(..).collect()
+}
+
+struct EditorLogLayer;
+
+impl<S: Subscriber> Layer<S> for EditorLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = RecordVisitor {
+            target: metadata.target().to_string(),
+            message: String::new(),
+            fields: vec![],
+        };
+        event.record(&mut visitor);
+
+        let level = match *metadata.level() {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Trace,
+        };
+
+        let mut queue = QUEUE.lock().unwrap();
+        if queue.len() >= MAX_QUEUED_RECORDS {
+            queue.pop_front();
+        }
+        queue.push_back(RuntimeToEditorMsg::Log {
+            level,
+            target: visitor.target,
+            message: visitor.message,
+            fields: visitor.fields,
+            timestamp: SystemTime::now(),
+        });
+    }
+}
+
+struct RecordVisitor {
+    target: String,
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            // records of the `log` crate carry their metadata as fields
+            "log.target" => self.target = value.to_string(),
+            name if name.starts_with("log.") => {}
+            name => self.fields.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            name if name.starts_with("log.") => {}
+            name => self.fields.push((name.to_string(), format!("{:?}", value))),
+        }
+    }
+}
@@ -1,29 +1,55 @@
-use std::{any::TypeId, ptr::NonNull};
+use std::{
+    any::TypeId,
+    cell::Cell,
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use bevy::{
+    asset::io::file::FileAssetReader,
+    diagnostic::{DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
     ecs::{
-        component::{ComponentId, ComponentInfo},
-        system::{EntityCommands, SystemParam, SystemState},
+        component::{ComponentId, ComponentInfo, Tick},
+        entity::EntityHashMap,
+        system::{Command, EntityCommands, SystemParam, SystemState},
+    },
+    hierarchy::despawn_with_children_recursive,
+    input::{
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
     },
-    input::keyboard::KeyboardInput,
     log,
     math::DVec2,
     prelude::*,
-    ptr::OwningPtr,
-    reflect::{
-        serde::{ReflectSerializer, TypedReflectDeserializer},
-        ReflectFromPtr, TypeRegistry,
-    },
+    reflect::{GetPath, ReflectFromPtr, ReflectFromReflect, TypeRegistration, TypeRegistry},
     scene::serialize_ron,
+    transform::commands::{PushChildInPlace, RemoveParentInPlace},
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
     winit::{
-        converters::{convert_element_state, convert_physical_key_code},
+        converters::{convert_element_state, convert_mouse_button, convert_physical_key_code},
         WindowAndInputEventWriters,
     },
 };
-use serde::de::DeserializeSeed;
+use roth_shared::{
+    handshake::{Capabilities, HandshakeError, HandshakeReply, Hello, RuntimeInfo},
+    launch::LaunchArgs,
+    recording::{Recorder, RecordingTransport, Side},
+    resolve_asset_path,
+    transport::{self, Connection, Transport},
+    ComponentData, EditorToRuntimeMsg, ErrorKind, PlayState, ReflectValue, Request, RequestId,
+    Response, RuntimeToEditorMsg, TypeRegistrySchema, HEARTBEAT_INTERVAL,
+};
 
-use ipc_channel::ipc::{IpcOneShotServer, IpcReceiver, IpcSender};
-use roth_shared::{EditorToRuntimeMsg, RonComponentSerialized, RuntimeToEditorMsg};
+use crate::{
+    editor_camera::{self, DeactivatedCamera, EditorCamera},
+    editor_diagnostics, editor_log,
+    editor_scene::{self, SceneLoads},
+};
 
 #[derive(States, Default, Debug, Clone, Hash, Eq, PartialEq)]
 pub enum EditorState {
@@ -34,163 +60,1297 @@ pub enum EditorState {
     Game,
 }
 
-pub struct EditorPlugin;
+/// Attaches the app to the editor: answers its requests, forwards its input to the window and keeps
+/// it up to date on the world.
+pub struct EditorPlugin {
+    pub connection: EditorConnection,
+    /// what the paths in `Save` are relative to, `None` uses the folder the `AssetServer` loads
+    /// from, so a saved scene can be loaded by the same path
+    pub asset_root: Option<PathBuf>,
+    /// how often fps, frame time, entity and asset counts are sent to the editor, `None` sends
+    /// none of them
+    pub diagnostics_interval: Option<Duration>,
+}
+
+impl Default for EditorPlugin {
+    fn default() -> Self {
+        Self {
+            connection: EditorConnection::CommandLine,
+            asset_root: None,
+            diagnostics_interval: None,
+        }
+    }
+}
+
+impl EditorPlugin {
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            connection: EditorConnection::Transport(transport),
+            ..default()
+        }
+    }
+}
+
+/// How [`EditorPlugin`] reaches the editor.
+#[derive(Clone)]
+pub enum EditorConnection {
+    /// [`LaunchArgs`] from the command line or the environment, which is how the editor starts the
+    /// runtime. Without any the runtime runs standalone and the plugin does nothing, so the same
+    /// binary can be shipped.
+    CommandLine,
+    Connect(LaunchArgs),
+    /// an already connected transport, the handshake still happens over it
+    Transport(Arc<dyn Transport>),
+}
+
+/// Connects to the editor that started the runtime with `launch`.
+fn connect(launch: &LaunchArgs) -> Result<Arc<dyn Transport>, String> {
+    let transport = transport::connect(launch.transport, &launch.ipc)
+        .map_err(|err| format!("could not connect to {}: {}", launch.ipc, err))?;
+    let Some(path) = &launch.record else {
+        return Ok(transport);
+    };
+    match Recorder::create(path) {
+        Ok(recorder) => {
+            log::info!("recording the editor session to {}", path.display());
+            Ok(Arc::new(RecordingTransport::new(
+                transport,
+                Arc::new(recorder),
+                Side::Runtime,
+            )))
+        }
+        Err(err) => {
+            log::error!("not recording to {}: {}", path.display(), err);
+            Ok(transport)
+        }
+    }
+}
+
+#[derive(Resource)]
+struct EditorConfig {
+    asset_root: PathBuf,
+}
 
 struct EditorIpc {
-    sender: IpcSender<RuntimeToEditorMsg>,
-    receiver: IpcReceiver<EditorToRuntimeMsg>,
+    connection: Connection<Response, Request>,
+    /// set once sending failed, the game keeps running without the editor
+    disconnected: Cell<bool>,
+}
+
+impl EditorIpc {
+    /// Sends `msg` to the editor as the answer to `request_id`, or unprompted when it's `None`.
+    fn send(&self, request_id: Option<RequestId>, msg: RuntimeToEditorMsg) {
+        if self.disconnected.get() {
+            return;
+        }
+        if let Err(err) = self.connection.send(&Response { request_id, msg }) {
+            log::error!("lost the connection to the editor: {}", err);
+            self.disconnected.set(true);
+        }
+    }
+
+    /// Sends the answer to `request_id`, or an `Error` when the request failed.
+    fn answer(&self, request_id: RequestId, result: Result<RuntimeToEditorMsg, RequestError>) {
+        let msg = result.unwrap_or_else(|err| {
+            log::error!("{}", err.message);
+            RuntimeToEditorMsg::Error {
+                request_id: Some(request_id),
+                kind: err.kind,
+                message: err.message,
+            }
+        });
+        self.send(Some(request_id), msg);
+    }
 }
 
 impl Plugin for EditorPlugin {
     fn build(&self, app: &mut App) {
-        let ipc_server = std::env::args()
-            .nth(4)
-            .expect("expected ipc server as second argument");
+        let transport = match &self.connection {
+            EditorConnection::CommandLine => match LaunchArgs::from_env() {
+                Ok(Some(launch)) => connect(&launch),
+                Ok(None) => {
+                    log::info!("no editor to attach to, running standalone");
+                    return;
+                }
+                Err(err) => Err(err),
+            },
+            EditorConnection::Connect(launch) => connect(launch),
+            EditorConnection::Transport(transport) => Ok(transport.clone()),
+        };
+        let transport = match transport {
+            Ok(transport) => transport,
+            Err(err) => {
+                log::error!("not attaching to the editor: {}", err);
+                return;
+            }
+        };
 
-        let (editor_to_runtime_sender, editor_receiver): (
-            IpcSender<EditorToRuntimeMsg>,
-            IpcReceiver<EditorToRuntimeMsg>,
-        ) = ipc_channel::ipc::channel().unwrap();
-        let editor_oneshot: IpcSender<(IpcSender<EditorToRuntimeMsg>, String)> =
-            IpcSender::connect(ipc_server).unwrap();
+        let mut capabilities = Capabilities::INPUT
+            | Capabilities::ENTITIES
+            | Capabilities::ENTITY_DIFFS
+            | Capabilities::SCENES
+            | Capabilities::TYPE_REGISTRY
+            | Capabilities::RESOURCES
+            | Capabilities::HEARTBEAT;
+        if editor_log::is_installed() {
+            capabilities = capabilities | Capabilities::LOGS;
+        }
+        if self.diagnostics_interval.is_some() {
+            capabilities = capabilities | Capabilities::DIAGNOSTICS;
+        }
+        let hello = Hello::runtime(
+            capabilities,
+            RuntimeInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        );
+        if let Err(err) = transport::send_message(&*transport, &hello) {
+            log::error!("not attaching to the editor: {}", err);
+            return;
+        }
 
-        let (server, server_name) =
-            IpcOneShotServer::<IpcSender<RuntimeToEditorMsg>>::new().unwrap();
-        editor_oneshot
-            .send((editor_to_runtime_sender, server_name))
-            .unwrap();
+        match transport::recv_message::<HandshakeReply>(&*transport) {
+            Ok(Ok(editor_hello)) => {
+                if let Err(err) = hello.check_compatible(&editor_hello) {
+                    log::error!("not attaching to the editor: {}", err);
+                    return;
+                }
+            }
+            Ok(Err(err)) => {
+                log::error!(
+                    "not attaching to the editor: {}",
+                    HandshakeError::Refused(Box::new(err))
+                );
+                return;
+            }
+            Err(err) => {
+                log::error!("not attaching to the editor: {}", HandshakeError::from(err));
+                return;
+            }
+        };
 
-        let (_, editor_sender) = server.accept().unwrap();
+        let asset_root = self.asset_root.clone().unwrap_or_else(|| {
+            let file_path = app
+                .get_added_plugins::<AssetPlugin>()
+                .first()
+                .map(|plugin| plugin.file_path.clone())
+                .unwrap_or_else(|| AssetPlugin::default().file_path);
+            FileAssetReader::get_base_path().join(file_path)
+        });
 
         app.init_state::<EditorState>()
             .insert_non_send_resource(EditorIpc {
-                sender: editor_sender,
-                receiver: editor_receiver,
+                connection: Connection::new(transport),
+                disconnected: Cell::new(false),
             })
-            .add_systems(Update, (handle_ipc,).run_if(in_state(EditorState::Editor)))
+            .insert_resource(EditorConfig { asset_root })
+            .init_resource::<SyncedEntities>()
+            .init_resource::<SceneLoads>()
+            // in `EditorState::Game` as well, the editor still forwards input and stops the game
+            .add_systems(
+                Update,
+                (handle_ipc, send_scene_loads, send_entity_changes).chain(),
+            )
+            .insert_resource(HeartbeatTimer(Timer::new(
+                HEARTBEAT_INTERVAL,
+                TimerMode::Repeating,
+            )))
+            .add_systems(Last, (send_logs, send_heartbeat))
+            .add_systems(
+                Update,
+                (
+                    editor_camera::deactivate_game_cameras,
+                    editor_camera::move_editor_camera,
+                )
+                    .run_if(in_state(EditorState::Editor)),
+            )
             .add_systems(OnEnter(EditorState::Editor), setup_editor)
-            .add_systems(OnExit(EditorState::Editor), cleanup_editor);
+            .add_systems(
+                OnExit(EditorState::Editor),
+                (cleanup_editor, editor_camera::activate_game_cameras),
+            );
+        if let Some(interval) = self.diagnostics_interval {
+            if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+                app.add_plugins(FrameTimeDiagnosticsPlugin);
+            }
+            if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+                app.add_plugins(EntityCountDiagnosticsPlugin);
+            }
+            app.insert_resource(DiagnosticsTimer(Timer::new(interval, TimerMode::Repeating)))
+                .add_systems(Last, send_diagnostics);
+        }
+    }
+}
+
+fn send_logs(ipc: NonSend<EditorIpc>) {
+    for record in editor_log::take_records() {
+        ipc.send(None, record);
+    }
+}
+
+#[derive(Resource)]
+struct HeartbeatTimer(Timer);
+
+fn send_heartbeat(
+    time: Res<Time<Real>>,
+    mut timer: ResMut<HeartbeatTimer>,
+    ipc: NonSend<EditorIpc>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        ipc.send(None, RuntimeToEditorMsg::Heartbeat);
+    }
+}
+
+fn send_scene_loads(world: &mut World) {
+    for response in editor_scene::poll_scene_loads(world) {
+        let ipc = world.get_non_send_resource::<EditorIpc>().unwrap();
+        ipc.send(response.request_id, response.msg);
     }
 }
 
-fn send_entities(world: &mut World) {
+#[derive(Resource)]
+struct DiagnosticsTimer(Timer);
+
+fn send_diagnostics(world: &mut World) {
+    let delta = world.resource::<Time<Real>>().delta();
+    if !world
+        .resource_mut::<DiagnosticsTimer>()
+        .0
+        .tick(delta)
+        .just_finished()
+    {
+        return;
+    }
+
+    editor_diagnostics::measure_asset_counts(world);
+    let diagnostics = editor_diagnostics::diagnostic_values(world.resource::<DiagnosticsStore>());
     let ipc = world.get_non_send_resource::<EditorIpc>().unwrap();
-    let type_registry = world.resource::<AppTypeRegistry>().read();
+    ipc.send(None, RuntimeToEditorMsg::Diagnostics { diagnostics });
+}
+
+/// What the editor was last told about, so [`send_entity_changes`] only has to send what changed since.
+#[derive(Resource, Default)]
+struct SyncedEntities {
+    /// `None` until the editor asked for the entities for the first time
+    last_sync: Option<Tick>,
+    /// hash of the last value sent for every component, so components that are marked as changed
+    /// every frame without actually changing aren't sent over and over
+    components: HashMap<Entity, HashMap<ComponentId, u64>>,
+    /// children of every entity that has any
+    children: HashMap<Entity, Vec<Entity>>,
+}
+
+fn serialize_component(
+    world: &World,
+    entity: EntityRef,
+    component_id: ComponentId,
+    type_registry: &TypeRegistry,
+) -> ComponentData {
+    let component_info = world.components().get_info(component_id).unwrap();
+    let mut component = ComponentData {
+        type_name: component_info.name().to_string(),
+        value: None,
+    };
+
+    // components without a rust type or `ReflectFromPtr` can't be reflected, the editor only
+    // gets their name
+    let Some(type_registration) = component_info
+        .type_id()
+        .and_then(|type_id| type_registry.get(type_id))
+    else {
+        return component;
+    };
+    let Some(reflect_from_ptr) = type_registration.data::<ReflectFromPtr>() else {
+        return component;
+    };
+    let component_ptr = entity.get_by_id(component_id).unwrap();
+    let reflect = unsafe { reflect_from_ptr.as_reflect(component_ptr) };
+    component.value = ReflectValue::from_reflect(reflect, type_registry).ok();
+    component
+}
+
+/// `None` when `registration` isn't a reflected resource or it doesn't exist in the world.
+fn serialize_resource(
+    world: &World,
+    registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+) -> Option<ComponentData> {
+    let reflect = registration.data::<ReflectResource>()?.reflect(world)?;
+    Some(ComponentData {
+        type_name: registration.type_info().type_path().to_string(),
+        value: ReflectValue::from_reflect(reflect, type_registry).ok(),
+    })
+}
+
+fn children_of(entity: EntityRef) -> Vec<Entity> {
+    entity
+        .get::<Children>()
+        .map(|children| children.to_vec())
+        .unwrap_or_default()
+}
+
+fn hash_component(component: &ComponentData) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    component.value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn send_entities(world: &mut World, request_id: Option<RequestId>) {
+    let this_run = world.change_tick();
+    world.increment_change_tick();
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let mut synced = HashMap::default();
+    let mut hierarchy = vec![];
     let entities = world
         .iter_entities()
         .map(|entity| {
+            let children = children_of(entity);
+            if !children.is_empty() {
+                hierarchy.push((entity.id(), children));
+            }
+
             let components = entity
                 .archetype()
                 .components()
-                .filter_map(|component_id| {
-                    let component_info = world.components().get_info(component_id).unwrap();
-
-                    let type_id = component_info.type_id().unwrap();
-                    let Some(type_registration) = type_registry.get(type_id) else {
-                        return Some(RonComponentSerialized {
-                            type_name: component_info.name().to_string(),
-                            value: "Unit".to_string(),
-                        });
-                    };
-                    let reflect_from_ptr = type_registration.data::<ReflectFromPtr>().unwrap();
-                    let component_ptr = entity.get_by_id(component_id).unwrap();
-                    unsafe {
-                        let reflect = reflect_from_ptr.as_reflect(component_ptr);
-                        let serializer = ReflectSerializer::new(reflect, &type_registry);
-                        let Ok(ron) = roth_shared::ron::to_string(&serializer) else {
-                            return Some(RonComponentSerialized {
-                                type_name: component_info.name().to_string(),
-                                value: "Unit".to_string(),
-                            });
-                        };
-                        Some(RonComponentSerialized {
-                            type_name: component_info.name().to_string(),
-                            value: ron,
-                        })
-                    }
+                .map(|component_id| {
+                    serialize_component(world, entity, component_id, &type_registry)
                 })
                 .collect::<Vec<_>>();
 
+            synced.insert(
+                entity.id(),
+                entity
+                    .archetype()
+                    .components()
+                    .zip(components.iter().map(hash_component))
+                    .collect(),
+            );
+
             (entity.id(), components)
         })
         .collect::<Vec<_>>();
 
-    ipc.sender
-        .send(RuntimeToEditorMsg::Entities { entities })
-        .unwrap();
-}
+    world.insert_resource(SyncedEntities {
+        last_sync: Some(this_run),
+        components: synced,
+        children: hierarchy.iter().cloned().collect(),
+    });
 
-fn handle_ipc(mut world: &mut World) {
     let ipc = world.get_non_send_resource::<EditorIpc>().unwrap();
+    ipc.send(
+        request_id,
+        RuntimeToEditorMsg::Entities {
+            entities,
+            hierarchy,
+        },
+    );
+}
+
+/// Sends the editor a diff of everything that changed since the last sync, using change detection.
+fn send_entity_changes(world: &mut World) {
+    let Some(last_sync) = world.resource::<SyncedEntities>().last_sync else {
+        return;
+    };
+    let this_run = world.change_tick();
+    world.increment_change_tick();
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let previous = world.resource::<SyncedEntities>();
+
+    let mut spawned = vec![];
+    let mut components_changed = vec![];
+    let mut components_removed = vec![];
+    let mut hierarchy_changed = vec![];
+    let mut synced: HashMap<Entity, HashMap<ComponentId, u64>> = HashMap::default();
+    let mut synced_children = HashMap::default();
+
+    for entity in world.iter_entities() {
+        let children = children_of(entity);
+        let previous_children = previous
+            .children
+            .get(&entity.id())
+            .map_or(&[][..], |it| it.as_slice());
+        if children != previous_children {
+            hierarchy_changed.push((entity.id(), children.clone()));
+        }
+        if !children.is_empty() {
+            synced_children.insert(entity.id(), children);
+        }
+
+        let previous_components = previous.components.get(&entity.id());
+        let mut hashes = HashMap::default();
+        let mut changed = vec![];
+
+        for component_id in entity.archetype().components() {
+            let previous_hash = previous_components.and_then(|it| it.get(&component_id));
+            let is_changed = entity
+                .get_change_ticks_by_id(component_id)
+                .map_or(false, |ticks| ticks.is_changed(last_sync, this_run));
+
+            if let (Some(previous_hash), false) = (previous_hash, is_changed) {
+                hashes.insert(component_id, *previous_hash);
+                continue;
+            }
+
+            let component = serialize_component(world, entity, component_id, &type_registry);
+            let hash = hash_component(&component);
+            if previous_hash != Some(&hash) {
+                changed.push(component);
+            }
+            hashes.insert(component_id, hash);
+        }
+
+        match previous_components {
+            None => spawned.push((entity.id(), changed)),
+            Some(previous_components) => {
+                let removed = previous_components
+                    .keys()
+                    .filter(|component_id| !hashes.contains_key(*component_id))
+                    .filter_map(|component_id| world.components().get_info(*component_id))
+                    .map(|component_info| component_info.name().to_string())
+                    .collect::<Vec<_>>();
+
+                if !changed.is_empty() {
+                    components_changed.push((entity.id(), changed));
+                }
+                if !removed.is_empty() {
+                    components_removed.push((entity.id(), removed));
+                }
+            }
+        }
+
+        synced.insert(entity.id(), hashes);
+    }
+
+    let despawned = previous
+        .components
+        .keys()
+        .filter(|entity| !synced.contains_key(*entity))
+        .copied()
+        .collect::<Vec<_>>();
+
+    *world.resource_mut::<SyncedEntities>() = SyncedEntities {
+        last_sync: Some(this_run),
+        components: synced,
+        children: synced_children,
+    };
 
-    let Ok(msg) = ipc.receiver.try_recv() else {
+    if spawned.is_empty()
+        && despawned.is_empty()
+        && components_changed.is_empty()
+        && components_removed.is_empty()
+        && hierarchy_changed.is_empty()
+    {
         return;
+    }
+
+    let ipc = world.get_non_send_resource::<EditorIpc>().unwrap();
+    ipc.send(
+        None,
+        RuntimeToEditorMsg::EntitiesChanged {
+            spawned,
+            despawned,
+            components_changed,
+            components_removed,
+            hierarchy_changed,
+        },
+    );
+}
+
+/// Why a request failed, the editor gets it as a `RuntimeToEditorMsg::Error`.
+#[derive(Debug)]
+struct RequestError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl RequestError {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    fn entity_not_found(entity: Entity) -> Self {
+        Self::new(ErrorKind::NotFound, format!("{:?} doesn't exist", entity))
+    }
+
+    /// Prefixes the message with what was being done.
+    fn context(self, context: impl fmt::Display) -> Self {
+        Self::new(self.kind, format!("{}: {}", context, self.message))
+    }
+}
+
+/// Inserts `component` through its `ReflectComponent`, so it only works for types registered with
+/// `#[reflect(Component)]`. A component the entity has already is updated with `apply` instead.
+fn insert_reflected(
+    world: &mut World,
+    entity: Entity,
+    component: &ComponentData,
+) -> Result<(), RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (registration, reflect_component) =
+        reflect_component(&type_registry, &component.type_name)?;
+    let value = component.value.as_ref().ok_or_else(|| {
+        RequestError::new(
+            ErrorKind::InvalidValue,
+            format!("{} has no value", component.type_name),
+        )
+    })?;
+    let reflected = value
+        .to_reflect(registration, &type_registry)
+        .map_err(|err| {
+            RequestError::new(
+                ErrorKind::InvalidValue,
+                format!("could not read {}: {}", component.type_name, err),
+            )
+        })?;
+
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .ok_or_else(|| RequestError::entity_not_found(entity))?;
+    if reflect_component.contains((&entity_mut).into()) {
+        reflect_component.apply(&mut entity_mut, &*reflected);
+        return Ok(());
+    }
+
+    // `insert` panics when it can't turn the dynamic value into the component, a concrete one
+    // always can
+    let concrete = concrete_value(registration, &*reflected)?;
+    reflect_component.insert(&mut entity_mut, &*concrete, &type_registry);
+    Ok(())
+}
+
+/// Builds a value of `registration`'s type from the dynamic `reflected`, with its `FromReflect` or
+/// by applying it to its `Default`.
+fn concrete_value(
+    registration: &TypeRegistration,
+    reflected: &dyn Reflect,
+) -> Result<Box<dyn Reflect>, RequestError> {
+    if let Some(reflect_from_reflect) = registration.data::<ReflectFromReflect>() {
+        if let Some(value) = reflect_from_reflect.from_reflect(reflected) {
+            return Ok(value);
+        }
+    }
+    if let Some(reflect_default) = registration.data::<ReflectDefault>() {
+        let mut value = reflect_default.default();
+        value.apply(reflected);
+        return Ok(value);
+    }
+    Err(RequestError::new(
+        ErrorKind::NotRegistered,
+        format!(
+            "{} can't be built from reflection, it needs `FromReflect` or `#[reflect(Default)]`",
+            registration.type_info().type_path()
+        ),
+    ))
+}
+
+fn remove_reflected(
+    world: &mut World,
+    entity: Entity,
+    type_name: &str,
+) -> Result<(), RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (_, reflect_component) = reflect_component(&type_registry, type_name)?;
+
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .ok_or_else(|| RequestError::entity_not_found(entity))?;
+    reflect_component.remove(&mut entity_mut);
+    Ok(())
+}
+
+/// Applies `value` to a single field of the component, `path` as `GetPath` takes it.
+fn set_component_field(
+    world: &mut World,
+    entity: Entity,
+    type_name: &str,
+    path: &str,
+    value: &ReflectValue,
+) -> Result<(), RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (_, reflect_component) = reflect_component(&type_registry, type_name)?;
+
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .ok_or_else(|| RequestError::entity_not_found(entity))?;
+    let mut component = reflect_component
+        .reflect_mut(&mut entity_mut)
+        .ok_or_else(|| {
+            RequestError::new(
+                ErrorKind::NotFound,
+                format!("{:?} has no {}", entity, type_name),
+            )
+        })?;
+    apply_at_path(&mut *component, path, value, &type_registry)
+}
+
+/// Overwrites the resource with `resource`, or inserts it when it doesn't exist yet. Returns the
+/// resource as it is afterwards.
+fn set_resource(
+    world: &mut World,
+    resource: &ComponentData,
+) -> Result<ComponentData, RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (registration, reflect_resource) = reflect_resource(&type_registry, &resource.type_name)?;
+    let value = resource.value.as_ref().ok_or_else(|| {
+        RequestError::new(
+            ErrorKind::InvalidValue,
+            format!("{} has no value", resource.type_name),
+        )
+    })?;
+    let reflected = value
+        .to_reflect(registration, &type_registry)
+        .map_err(|err| {
+            RequestError::new(
+                ErrorKind::InvalidValue,
+                format!("could not read {}: {}", resource.type_name, err),
+            )
+        })?;
+
+    if reflect_resource.reflect(world).is_some() {
+        reflect_resource.apply(world, &*reflected);
+    } else {
+        // `insert` panics just like `ReflectComponent::insert` does
+        let concrete = concrete_value(registration, &*reflected)?;
+        reflect_resource.insert(world, &*concrete);
+    }
+    serialize_resource(world, registration, &type_registry).ok_or_else(|| {
+        RequestError::new(
+            ErrorKind::NotFound,
+            format!("{} doesn't exist", resource.type_name),
+        )
+    })
+}
+
+/// Like [`set_component_field`] for a resource, returns the resource as it is afterwards.
+fn set_resource_field(
+    world: &mut World,
+    type_name: &str,
+    path: &str,
+    value: &ReflectValue,
+) -> Result<ComponentData, RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (registration, reflect_resource) = reflect_resource(&type_registry, type_name)?;
+
+    let not_found =
+        || RequestError::new(ErrorKind::NotFound, format!("{} doesn't exist", type_name));
+    let mut resource = reflect_resource.reflect_mut(world).ok_or_else(not_found)?;
+    apply_at_path(&mut *resource, path, value, &type_registry)?;
+
+    serialize_resource(world, registration, &type_registry).ok_or_else(not_found)
+}
+
+/// Overwrites the field at `path` with `value`, which is read as the field's type so a value of
+/// the wrong type is an error rather than a panic in `apply`.
+fn apply_at_path(
+    target: &mut dyn Reflect,
+    path: &str,
+    value: &ReflectValue,
+    type_registry: &TypeRegistry,
+) -> Result<(), RequestError> {
+    let field = target.reflect_path_mut(path).map_err(|err| {
+        RequestError::new(
+            ErrorKind::InvalidPath,
+            format!("no field at `{}`: {}", path, err),
+        )
+    })?;
+    let registration = field
+        .get_represented_type_info()
+        .and_then(|info| type_registry.get(info.type_id()))
+        .ok_or_else(|| {
+            RequestError::new(
+                ErrorKind::NotRegistered,
+                format!("the type at `{}` isn't registered", path),
+            )
+        })?;
+    let reflected = value
+        .to_reflect(registration, type_registry)
+        .map_err(|err| {
+            RequestError::new(
+                ErrorKind::InvalidValue,
+                format!("could not read the value for `{}`: {}", path, err),
+            )
+        })?;
+    field.apply(&*reflected);
+    Ok(())
+}
+
+fn reflect_component<'a>(
+    type_registry: &'a TypeRegistry,
+    type_name: &str,
+) -> Result<(&'a TypeRegistration, &'a ReflectComponent), RequestError> {
+    let registration = registration(type_registry, type_name)?;
+    let reflect_component = registration.data::<ReflectComponent>().ok_or_else(|| {
+        RequestError::new(
+            ErrorKind::NotRegistered,
+            format!("{} isn't reflected as a component", type_name),
+        )
+    })?;
+    Ok((registration, reflect_component))
+}
+
+fn reflect_resource<'a>(
+    type_registry: &'a TypeRegistry,
+    type_name: &str,
+) -> Result<(&'a TypeRegistration, &'a ReflectResource), RequestError> {
+    let registration = registration(type_registry, type_name)?;
+    let reflect_resource = registration.data::<ReflectResource>().ok_or_else(|| {
+        RequestError::new(
+            ErrorKind::NotRegistered,
+            format!("{} isn't reflected as a resource", type_name),
+        )
+    })?;
+    Ok((registration, reflect_resource))
+}
+
+fn registration<'a>(
+    type_registry: &'a TypeRegistry,
+    type_name: &str,
+) -> Result<&'a TypeRegistration, RequestError> {
+    type_registry.get_with_type_path(type_name).ok_or_else(|| {
+        RequestError::new(
+            ErrorKind::NotRegistered,
+            format!("{} isn't registered", type_name),
+        )
+    })
+}
+
+/// Spawns a copy of `entity` next to it, and copies of its descendants when `recursive`. Returns
+/// the spawned entities, the copy of `entity` first.
+fn duplicate_entity(
+    world: &mut World,
+    entity: Entity,
+    recursive: bool,
+) -> Result<Vec<Entity>, RequestError> {
+    let mut spawned = vec![];
+    if let Err(err) = duplicate_tree(world, entity, None, recursive, &mut spawned) {
+        // the copies are a hierarchy of their own until the copy of `entity` gets its parent
+        if let Some(copy) = spawned.first() {
+            despawn_with_children_recursive(world, *copy);
+        }
+        return Err(err);
+    }
+
+    if let Some(parent) = world.get::<Parent>(entity).map(|it| it.get()) {
+        world.entity_mut(parent).add_child(spawned[0]);
+    }
+    Ok(spawned)
+}
+
+/// Copies `entity` under `parent`, pushing the copy and the copies of its descendants to `spawned`.
+fn duplicate_tree(
+    world: &mut World,
+    entity: Entity,
+    parent: Option<Entity>,
+    recursive: bool,
+    spawned: &mut Vec<Entity>,
+) -> Result<(), RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let components = cloned_components(world, entity, &type_registry)?;
+
+    let copy = world.spawn_empty().id();
+    spawned.push(copy);
+    let mut copy_mut = world.entity_mut(copy);
+    for (reflect_component, value) in components {
+        reflect_component.insert(&mut copy_mut, &*value, &type_registry);
+    }
+    if let Some(parent) = parent {
+        copy_mut.set_parent(parent);
+    }
+
+    if recursive {
+        let children = world
+            .get::<Children>(entity)
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+        for child in children {
+            duplicate_tree(world, child, Some(copy), recursive, spawned)?;
+        }
+    }
+    Ok(())
+}
+
+/// A clone of each of the entity's reflected components, but for `Parent` and `Children`, which
+/// would point at the original's relatives.
+fn cloned_components(
+    world: &World,
+    entity: Entity,
+    type_registry: &TypeRegistry,
+) -> Result<Vec<(ReflectComponent, Box<dyn Reflect>)>, RequestError> {
+    let entity_ref = world
+        .get_entity(entity)
+        .ok_or_else(|| RequestError::entity_not_found(entity))?;
+    let hierarchy = [TypeId::of::<Parent>(), TypeId::of::<Children>()];
+
+    let mut components = vec![];
+    for component_id in entity_ref.archetype().components() {
+        let Some(registration) = world
+            .components()
+            .get_info(component_id)
+            .and_then(|info| info.type_id())
+            .filter(|type_id| !hierarchy.contains(type_id))
+            .and_then(|type_id| type_registry.get(type_id))
+        else {
+            continue;
+        };
+        let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+            continue;
+        };
+        if let Some(value) = reflect_component.reflect(entity_ref) {
+            // `insert` panics on values it can't turn into the component, see `insert_reflected`
+            let value = concrete_value(registration, value)?;
+            components.push((reflect_component.clone(), value));
+        }
+    }
+    Ok(components)
+}
+
+/// Moves `child` under `parent`, or to the root with `None`, changing its `Transform` so it stays
+/// where it is.
+fn set_parent(
+    world: &mut World,
+    child: Entity,
+    parent: Option<Entity>,
+) -> Result<(), RequestError> {
+    if world.get_entity(child).is_none() {
+        return Err(RequestError::entity_not_found(child));
+    }
+    let Some(parent) = parent else {
+        RemoveParentInPlace { child }.apply(world);
+        return Ok(());
+    };
+    if world.get_entity(parent).is_none() {
+        return Err(RequestError::entity_not_found(parent));
+    }
+
+    // bevy doesn't check for cycles, they'd hang transform propagation
+    let mut ancestor = Some(parent);
+    while let Some(entity) = ancestor {
+        if entity == child {
+            return Err(RequestError::new(
+                ErrorKind::InvalidValue,
+                format!(
+                    "{:?} can't become a child of itself or one of its descendants",
+                    child
+                ),
+            ));
+        }
+        ancestor = world.get::<Parent>(entity).map(|it| it.get());
+    }
+
+    PushChildInPlace { parent, child }.apply(world);
+    Ok(())
+}
+
+/// Writes everything but the editor's own entities to `path` in the asset folder, returns how many
+/// bytes were written. While playing it's the world from before `Play` that's written.
+fn save_scene(world: &World, path: &str) -> Result<usize, String> {
+    let file_path = resolve_asset_path(&world.resource::<EditorConfig>().asset_root, path)?;
+
+    let registry = world.resource::<AppTypeRegistry>();
+    let ron = match world.get_resource::<PlaySnapshot>() {
+        Some(snapshot) => snapshot.scene.serialize_ron(registry),
+        None => scene_snapshot(world).serialize_ron(registry),
+    }
+    .map_err(|err| format!("error serializing the scene: {}", err))?;
+    std::fs::write(&file_path, &ron)
+        .map_err(|err| format!("error writing {}: {}", file_path.display(), err))?;
+    Ok(ron.len())
+}
+
+/// Whether `entity` is part of the scene, the editor's own entities and the windows aren't.
+fn is_scene_entity(entity: &EntityRef) -> bool {
+    entity.contains_type_id(TypeId::of::<EditorMarker>()) == false
+        && entity.contains::<Window>() == false
+}
+
+/// The world as a scene, without the editor's own entities and the windows. The game's cameras
+/// are in it the way they're played with, not switched off for editing.
+fn scene_snapshot(world: &World) -> DynamicScene {
+    let entities_without_marker = world
+        .iter_entities()
+        .filter(is_scene_entity)
+        .map(|entity| entity.id());
+
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .allow_all()
+        .deny_resource::<Time>()
+        .deny_resource::<Time<Real>>()
+        .deny_resource::<Time<Virtual>>()
+        .deny_resource::<Time<Fixed>>()
+        .deny_resource::<GizmoConfig>()
+        .deny::<bevy::window::Window>()
+        .deny::<bevy::window::PrimaryWindow>()
+        .extract_resources()
+        .extract_entities(entities_without_marker)
+        .build();
+
+    for entity in &mut scene.entities {
+        if world.get::<DeactivatedCamera>(entity.entity).is_none() {
+            continue;
+        }
+        let camera = entity
+            .components
+            .iter_mut()
+            .find(|component| component.represents::<Camera>());
+        if let Some(Ok(is_active)) = camera.map(|camera| camera.reflect_path_mut("is_active")) {
+            is_active.apply(&true);
+        }
+    }
+    scene
+}
+
+/// The world as it was when `Play` was sent, put back on `Stop`. Only there while playing.
+#[derive(Resource)]
+struct PlaySnapshot {
+    scene: DynamicScene,
+    /// the components each entity had that `scene` doesn't hold, e.g. because they aren't
+    /// reflected, they're left alone on `Stop`
+    kept_components: EntityHashMap<HashSet<ComponentId>>,
+}
+
+impl PlaySnapshot {
+    fn take(world: &World) -> Self {
+        let scene = scene_snapshot(world);
+        let in_scene = scene
+            .entities
+            .iter()
+            .map(|entity| {
+                let components = entity
+                    .components
+                    .iter()
+                    .filter_map(|component| {
+                        let type_id = component.get_represented_type_info()?.type_id();
+                        world.components().get_id(type_id)
+                    })
+                    .collect::<HashSet<_>>();
+                (entity.entity, components)
+            })
+            .collect::<EntityHashMap<_>>();
+
+        let kept_components = world
+            .iter_entities()
+            .filter(is_scene_entity)
+            .map(|entity| {
+                let in_scene = in_scene.get(&entity.id());
+                let kept = entity
+                    .archetype()
+                    .components()
+                    .filter(|id| in_scene.map_or(true, |in_scene| !in_scene.contains(id)))
+                    .collect::<HashSet<_>>();
+                (entity.id(), kept)
+            })
+            .collect();
+
+        Self {
+            scene,
+            kept_components,
+        }
+    }
+}
+
+/// Starts the game, or resumes it when it's paused.
+fn play(world: &mut World) -> PlayState {
+    if !world.contains_resource::<PlaySnapshot>() {
+        let snapshot = PlaySnapshot::take(world);
+        world.insert_resource(snapshot);
+        world
+            .resource_mut::<NextState<EditorState>>()
+            .set(EditorState::Game);
+    }
+    world.resource_mut::<Time<Virtual>>().unpause();
+    PlayState::Playing
+}
+
+fn pause(world: &mut World) -> Result<PlayState, RequestError> {
+    if !world.contains_resource::<PlaySnapshot>() {
+        return Err(RequestError::new(
+            ErrorKind::InvalidState,
+            "there is no game playing",
+        ));
+    }
+    world.resource_mut::<Time<Virtual>>().pause();
+    Ok(PlayState::Paused)
+}
+
+/// Restores the world from the [`PlaySnapshot`] and goes back to editing.
+fn stop(world: &mut World) -> Result<PlayState, RequestError> {
+    let Some(snapshot) = world.remove_resource::<PlaySnapshot>() else {
+        return Err(RequestError::new(
+            ErrorKind::InvalidState,
+            "there is no game playing",
+        ));
     };
+    world.resource_mut::<Time<Virtual>>().unpause();
+    world
+        .resource_mut::<NextState<EditorState>>()
+        .set(EditorState::Editor);
+    restore_snapshot(world, &snapshot)?;
+    Ok(PlayState::Editing)
+}
+
+/// Puts the world back the way it was when `snapshot` was taken. The entities that are still
+/// around keep their ids, so the editor's selection stays, those that were despawned while playing
+/// come back with new ones, without the components the scene couldn't hold.
+fn restore_snapshot(world: &mut World, snapshot: &PlaySnapshot) -> Result<(), RequestError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let entities = world
+        .query_filtered::<Entity, (Without<EditorMarker>, Without<Window>)>()
+        .iter(world)
+        .collect::<Vec<_>>();
+
+    let mut entity_map = EntityHashMap::default();
+    for entity in entities {
+        let Some(kept) = snapshot.kept_components.get(&entity) else {
+            // spawned while playing
+            world.despawn(entity);
+            continue;
+        };
+        // the ones added while playing have to go, and those in the scene are inserted again
+        // rather than applied, applying a list doesn't remove what was pushed onto it. Only
+        // reflected ones can be removed without knowing their type, the others added while
+        // playing stay.
+        let mut entity_mut = world.entity_mut(entity);
+        let removed = entity_mut
+            .archetype()
+            .components()
+            .filter(|id| !kept.contains(id))
+            .filter_map(|id| {
+                let type_id = entity_mut.world().components().get_info(id)?.type_id()?;
+                type_registry.get_type_data::<ReflectComponent>(type_id)
+            })
+            .collect::<Vec<_>>();
+        for reflect_component in removed {
+            reflect_component.remove(&mut entity_mut);
+        }
+        entity_map.insert(entity, entity);
+    }
+
+    snapshot
+        .scene
+        .write_to_world(world, &mut entity_map)
+        .map_err(|err| {
+            RequestError::new(
+                ErrorKind::InvalidValue,
+                format!("could not restore the world from before playing: {}", err),
+            )
+        })
+}
+
+/// Handles everything the editor sent since the last frame, with one request a frame the answers
+/// fall further and further behind the requests awaiting them.
+fn handle_ipc(world: &mut World) {
+    while let Ok(Some(request)) = world.non_send_resource::<EditorIpc>().connection.try_recv() {
+        handle_request(world, request);
+    }
+}
+
+fn handle_request(mut world: &mut World, Request { id, msg }: Request) {
+    let ipc = world.get_non_send_resource::<EditorIpc>().unwrap();
 
     match msg {
         EditorToRuntimeMsg::GetEntities => {
-            send_entities(&mut world);
+            send_entities(&mut world, Some(id));
             return;
         }
 
-        EditorToRuntimeMsg::InsertComponent {
-            entity,
-            component: ron_component,
-        } => {
-            let type_registry_arc = (**world.resource::<AppTypeRegistry>()).clone();
-            let type_registry = type_registry_arc.read();
-            let component = world
-                .components()
-                .iter()
-                .find(|component| component.name() == ron_component.type_name)
-                .unwrap()
-                .clone();
-
-            let type_id = component.type_id().unwrap();
-            let Some(type_registration) = type_registry.get(type_id) else {
-                return;
+        EditorToRuntimeMsg::GetTypeRegistry => {
+            let schema = {
+                let type_registry = world.resource::<AppTypeRegistry>().read();
+                TypeRegistrySchema::from_registry(&type_registry)
             };
+            ipc.send(Some(id), RuntimeToEditorMsg::TypeRegistry { schema });
+            return;
+        }
 
-            let Some(reflect_from_ptr) = type_registration.data::<ReflectFromPtr>() else {
-                return;
+        EditorToRuntimeMsg::GetResources => {
+            let type_registry = world.resource::<AppTypeRegistry>().read();
+            let mut resources = type_registry
+                .iter()
+                .filter_map(|registration| serialize_resource(world, registration, &type_registry))
+                .collect::<Vec<_>>();
+            resources.sort_by(|a, b| a.type_name.cmp(&b.type_name));
+            ipc.send(Some(id), RuntimeToEditorMsg::Resources { resources });
+            return;
+        }
+
+        EditorToRuntimeMsg::SetResource { resource } => {
+            let result = set_resource(world, &resource)
+                .map(|resource| RuntimeToEditorMsg::ResourceSet { resource })
+                .map_err(|err| err.context(format!("can't set {}", resource.type_name)));
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::InsertComponent { entity, component } => {
+            let type_name = component.type_name.clone();
+            let result = insert_reflected(world, entity, &component)
+                .map(|()| RuntimeToEditorMsg::ComponentInserted {
+                    entity,
+                    type_name: type_name.clone(),
+                })
+                .map_err(|err| {
+                    err.context(format!("can't insert {} into {:?}", type_name, entity))
+                });
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::SpawnEntity { components } => {
+            let entity = world.spawn_empty().id();
+            let result = components
+                .iter()
+                .try_for_each(|component| insert_reflected(world, entity, component))
+                .map(|()| RuntimeToEditorMsg::EntitySpawned { entity })
+                .map_err(|err| err.context("can't spawn the entity"));
+            if result.is_err() {
+                world.despawn(entity);
+            }
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::DespawnEntity { entity, recursive } => {
+            let result = match world.get_entity_mut(entity) {
+                Some(mut entity_mut) => {
+                    if !recursive {
+                        entity_mut.clear_children();
+                    }
+                    despawn_with_children_recursive(world, entity);
+                    Ok(RuntimeToEditorMsg::EntityDespawned { entity })
+                }
+                None => Err(RequestError::entity_not_found(entity).context("can't despawn")),
             };
-            // only json words, RON fails here
-            let mut deserializer =
-                roth_shared::serde_json::de::Deserializer::from_str(&ron_component.value);
-            let reflect_deserializer =
-                TypedReflectDeserializer::new(&type_registration, &type_registry);
-
-            let reflected = reflect_deserializer.deserialize(&mut deserializer).unwrap();
-
-            unsafe {
-                let mut owning_ptr =
-                    OwningPtr::new(NonNull::new(std::alloc::alloc(component.layout())).unwrap());
-                let reflect = reflect_from_ptr.as_reflect_mut(owning_ptr.as_mut());
-                reflect.apply(&*reflected);
-
-                world
-                    .get_entity_mut(entity)
-                    .unwrap()
-                    .insert_by_id(component.id(), owning_ptr);
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::DuplicateEntity { entity, recursive } => {
+            let result = duplicate_entity(world, entity, recursive)
+                .map(|spawned| RuntimeToEditorMsg::EntityDuplicated {
+                    entity: spawned[0],
+                    spawned,
+                })
+                .map_err(|err| err.context(format!("can't duplicate {:?}", entity)));
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::RemoveComponent { entity, type_name } => {
+            let result = remove_reflected(world, entity, &type_name)
+                .map_err(|err| err.context(format!("can't remove {} from {:?}", type_name, entity)))
+                .map(|()| RuntimeToEditorMsg::ComponentRemoved { entity, type_name });
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::SetParent { child, parent } => {
+            let result = set_parent(world, child, parent)
+                .map(|()| RuntimeToEditorMsg::ParentSet { child, parent })
+                .map_err(|err| err.context(format!("can't move {:?} to {:?}", child, parent)));
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::SetField {
+            entity,
+            component,
+            path,
+            value,
+        } => {
+            let result = set_component_field(world, entity, &component, &path, &value)
+                .map_err(|err| {
+                    err.context(format!(
+                        "can't set {} of {} on {:?}",
+                        path, component, entity
+                    ))
+                })
+                .map(|()| RuntimeToEditorMsg::FieldSet {
+                    entity,
+                    component,
+                    path,
+                });
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::SetResourceField {
+            resource,
+            path,
+            value,
+        } => {
+            let result = set_resource_field(world, &resource, &path, &value)
+                .map(|resource| RuntimeToEditorMsg::ResourceSet { resource })
+                .map_err(|err| err.context(format!("can't set {} of {}", path, resource)));
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::Shutdown => {
+            world.send_event(bevy::app::AppExit);
+            return;
+        }
+        EditorToRuntimeMsg::Play => {
+            let state = play(world);
+            world
+                .non_send_resource::<EditorIpc>()
+                .send(Some(id), RuntimeToEditorMsg::PlayStateChanged { state });
+            return;
+        }
+        EditorToRuntimeMsg::Pause => {
+            let result = pause(world)
+                .map(|state| RuntimeToEditorMsg::PlayStateChanged { state })
+                .map_err(|err| err.context("can't pause"));
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::Stop => {
+            let result = stop(world)
+                .map(|state| RuntimeToEditorMsg::PlayStateChanged { state })
+                .map_err(|err| err.context("can't stop"));
+            world.non_send_resource::<EditorIpc>().answer(id, result);
+            return;
+        }
+        EditorToRuntimeMsg::Save { path } => {
+            let msg = match save_scene(world, &path) {
+                Ok(bytes) => {
+                    log::info!("saved the scene to {} ({} bytes)", path, bytes);
+                    RuntimeToEditorMsg::Saved { path, bytes }
+                }
+                Err(error) => {
+                    log::error!("error saving the scene to {}: {}", path, error);
+                    RuntimeToEditorMsg::SaveFailed { path, error }
+                }
             };
-
-            send_entities(&mut world);
+            ipc.send(Some(id), msg);
+            return;
+        }
+        EditorToRuntimeMsg::LoadScene { path, mode } => {
+            world.resource_scope(|world, mut scene_loads: Mut<SceneLoads>| {
+                scene_loads.start(world.resource::<AssetServer>(), id, path, mode);
+            });
             return;
         }
         _ => {}
     }
 
-    let mut system = SystemState::<(
-        Query<(Entity, &mut Window)>,
-        WindowAndInputEventWriters,
-        ResMut<Events<bevy::app::AppExit>>,
-        Commands,
-        Res<AssetServer>,
-    )>::new(&mut world);
-
-    let (mut windows, mut event_writers, mut app_exit_events, mut commands, asset_server) =
-        system.get_mut(world);
-    let (window_entity, mut window) = windows.single_mut();
+    // everything else is input for the window, which there is none of when running headless
+    let Ok(window_entity) = world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .get_single(world)
+    else {
+        return;
+    };
+
+    let mut system =
+        SystemState::<(Query<&mut Window>, WindowAndInputEventWriters)>::new(&mut world);
+    let (mut windows, mut event_writers) = system.get_mut(world);
+    let mut window = windows.get_mut(window_entity).unwrap();
     let scale_factor = window.scale_factor();
 
     match msg {
@@ -230,6 +1390,27 @@ fn handle_ipc(mut world: &mut World) {
                 window: window_entity,
             });
         }
+        EditorToRuntimeMsg::MouseInput { button, state } => {
+            event_writers.mouse_button_input.send(MouseButtonInput {
+                button: convert_mouse_button(button),
+                state: convert_element_state(state),
+                window: window_entity,
+            });
+        }
+        EditorToRuntimeMsg::MouseWheel { delta } => {
+            let (unit, x, y) = match delta {
+                winit::event::MouseScrollDelta::LineDelta(x, y) => (MouseScrollUnit::Line, x, y),
+                winit::event::MouseScrollDelta::PixelDelta(position) => {
+                    (MouseScrollUnit::Pixel, position.x as f32, position.y as f32)
+                }
+            };
+            event_writers.mouse_wheel_input.send(MouseWheel {
+                unit,
+                x,
+                y,
+                window: window_entity,
+            });
+        }
         EditorToRuntimeMsg::KeyboardInput {
             state,
             physical_key,
@@ -246,51 +1427,6 @@ fn handle_ipc(mut world: &mut World) {
                 window: window_entity,
             });
         }
-        EditorToRuntimeMsg::Shutdown => {
-            app_exit_events.send(bevy::app::AppExit);
-        }
-        EditorToRuntimeMsg::Save => {
-            let registry = world.resource::<AppTypeRegistry>();
-
-            let entities_without_marker = world.iter_entities().filter_map(|entity| {
-                if entity.contains_type_id(TypeId::of::<EditorMarker>()) == false {
-                    Some(entity.id())
-                } else {
-                    None
-                }
-            });
-
-            let scene = DynamicSceneBuilder::from_world(world)
-                .allow_all()
-                .deny_resource::<Time>()
-                .deny_resource::<Time<Real>>()
-                .deny_resource::<Time<Virtual>>()
-                .deny_resource::<Time<Fixed>>()
-                .deny_resource::<GizmoConfig>()
-                .deny::<bevy::window::Window>()
-                .deny::<bevy::window::PrimaryWindow>()
-                .extract_resources()
-                .extract_entities(entities_without_marker)
-                .build();
-
-            let ron = match scene.serialize_ron(&registry) {
-                Ok(ron) => ron,
-                Err(err) => {
-                    println!("error serializing scene: {:?}", err);
-                    return;
-                }
-            };
-
-            log::info!("Writing ron bytes: {}", ron.len());
-            std::fs::write("./example_bevy/assets/scenes/main.scn.ron", ron).unwrap();
-        }
-        EditorToRuntimeMsg::LoadScene { path } => {
-            commands.spawn(DynamicSceneBundle {
-                scene: asset_server.load(path),
-                ..default()
-            });
-        }
-
         _ => {}
     }
 }
@@ -357,10 +1493,16 @@ fn setup_editor(
     });
 
     // camera
-    commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(-2.5, 4.5, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
-    });
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(-2.5, 4.5, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        EditorCamera {
+            focus: Vec3::ZERO,
+            ..default()
+        },
+    ));
 }
 
 fn cleanup_editor(mut commands: Commands, query: Query<Entity, With<EditorMarker>>) {
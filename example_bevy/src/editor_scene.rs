@@ -0,0 +1,131 @@
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState},
+    hierarchy::despawn_with_children_recursive,
+    prelude::*,
+    scene::SceneSpawner,
+    utils::HashSet,
+};
+use roth_shared::{LoadMode, RequestId, Response, RuntimeToEditorMsg, SceneLoadStage};
+
+use crate::editor_plugin::EditorMarker;
+
+/// A `LoadScene` that's waiting for the `AssetServer`.
+struct SceneLoad {
+    request_id: RequestId,
+    path: String,
+    mode: LoadMode,
+    handle: Handle<DynamicScene>,
+    /// the last stage the editor was told about
+    stage: Option<SceneLoadStage>,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct SceneLoads(Vec<SceneLoad>);
+
+impl SceneLoads {
+    pub(crate) fn start(
+        &mut self,
+        asset_server: &AssetServer,
+        request_id: RequestId,
+        path: String,
+        mode: LoadMode,
+    ) {
+        let handle = asset_server.load(&path);
+        self.0.push(SceneLoad {
+            request_id,
+            path,
+            mode,
+            handle,
+            stage: None,
+        });
+    }
+}
+
+/// Spawns the scenes that finished loading, returns what to tell the editor about them.
+pub(crate) fn poll_scene_loads(world: &mut World) -> Vec<Response> {
+    let loads = std::mem::take(&mut world.resource_mut::<SceneLoads>().0);
+    let mut responses = vec![];
+    let mut pending = vec![];
+
+    for mut load in loads {
+        let load_states = world
+            .resource::<AssetServer>()
+            .get_load_states(&load.handle);
+        let stage = match load_states {
+            Some((LoadState::Failed, ..)) | Some((.., RecursiveDependencyLoadState::Failed)) => {
+                responses.push(Response {
+                    request_id: Some(load.request_id),
+                    msg: RuntimeToEditorMsg::SceneLoadFailed {
+                        path: load.path,
+                        // 0.13's `LoadState` doesn't say why, the asset server logged it though
+                        error: "the asset server couldn't load it, see the log for why".to_string(),
+                    },
+                });
+                continue;
+            }
+            Some((LoadState::Loaded, _, RecursiveDependencyLoadState::Loaded)) => {
+                let msg = match spawn_scene(world, &load) {
+                    Ok(entities) => RuntimeToEditorMsg::SceneLoaded {
+                        path: load.path,
+                        mode: load.mode,
+                        entities,
+                    },
+                    Err(error) => RuntimeToEditorMsg::SceneLoadFailed {
+                        path: load.path,
+                        error,
+                    },
+                };
+                responses.push(Response {
+                    request_id: Some(load.request_id),
+                    msg,
+                });
+                continue;
+            }
+            Some((LoadState::Loaded, ..)) => SceneLoadStage::LoadingDependencies,
+            _ => SceneLoadStage::Loading,
+        };
+
+        if load.stage != Some(stage) {
+            load.stage = Some(stage);
+            responses.push(Response {
+                request_id: None,
+                msg: RuntimeToEditorMsg::SceneLoading {
+                    path: load.path.clone(),
+                    stage,
+                },
+            });
+        }
+        pending.push(load);
+    }
+
+    world.resource_mut::<SceneLoads>().0.extend(pending);
+    responses
+}
+
+/// Returns how many entities the scene spawned.
+fn spawn_scene(world: &mut World, load: &SceneLoad) -> Result<usize, String> {
+    let instance = world
+        .resource_scope(|world, mut scene_spawner: Mut<SceneSpawner>| {
+            scene_spawner.spawn_dynamic_sync(world, &load.handle)
+        })
+        .map_err(|err| err.to_string())?;
+    let spawned = world
+        .resource::<SceneSpawner>()
+        .iter_instance_entities(instance)
+        .collect::<HashSet<_>>();
+
+    // only once it spawned, so a scene that fails to load doesn't leave the world empty
+    if load.mode == LoadMode::Replace {
+        // windows aren't part of a scene, despawning the primary one would close the app
+        let roots = world
+            .query_filtered::<Entity, (Without<Parent>, Without<EditorMarker>, Without<Window>)>()
+            .iter(world)
+            .filter(|entity| !spawned.contains(entity))
+            .collect::<Vec<_>>();
+        for entity in roots {
+            despawn_with_children_recursive(world, entity);
+        }
+    }
+
+    Ok(spawned.len())
+}
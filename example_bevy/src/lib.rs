@@ -0,0 +1,5 @@
+pub mod editor_camera;
+pub mod editor_diagnostics;
+pub mod editor_log;
+pub mod editor_plugin;
+pub mod editor_scene;
@@ -1,11 +1,10 @@
-mod editor_plugin;
-
 use std::any::TypeId;
 use std::f32::consts::PI;
+use std::time::Duration;
 
 use bevy::ecs::system::SystemState;
 use bevy::input::keyboard::KeyboardInput;
-use bevy::log;
+use bevy::log::{self, LogPlugin};
 use bevy::math::DVec2;
 use bevy::pbr::{Cascade, CascadeShadowConfigBuilder};
 use bevy::reflect::serde::ReflectSerializer;
@@ -18,21 +17,31 @@ use bevy::{
     pbr::ScreenSpaceAmbientOcclusionBundle,
     prelude::*,
 };
-use editor_plugin::EditorPlugin;
+use example_bevy::{editor_log::forward_logs, editor_plugin::EditorPlugin};
 use ipc_channel::ipc::{IpcOneShotServer, IpcReceiver, IpcSender};
 use roth_shared::{EditorToRuntimeMsg, RuntimeToEditorMsg};
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                focused: false,
-                decorations: false,
-                ..Default::default()
-            }),
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        focused: false,
+                        decorations: false,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(LogPlugin {
+                    update_subscriber: Some(forward_logs),
+                    ..Default::default()
+                }),
+        )
+        .add_plugins(EditorPlugin {
+            diagnostics_interval: Some(Duration::from_millis(250)),
             ..Default::default()
-        }))
-        .add_plugins(EditorPlugin)
+        })
         .register_type::<Vec<Cascade>>()
         .register_type::<Vec<f32>>()
         .register_type::<HashMap<Entity, Vec<bevy::pbr::Cascade>>>()
@@ -0,0 +1,137 @@
+//! Shared between the test binaries, each of them only uses part of it.
+#![allow(dead_code)]
+
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bevy::{input::InputPlugin, prelude::*, scene::ScenePlugin, window::ExitCondition};
+use example_bevy::editor_plugin::EditorPlugin;
+use roth_shared::{
+    handshake::{Capabilities, HandshakeReply, Hello},
+    transport::{self, Connection, MemoryTransport},
+    EditorToRuntimeMsg, Request, RequestId, Response, RuntimeToEditorMsg,
+};
+
+/// A headless app with the [`EditorPlugin`] attached, and the editor's end of its connection.
+pub struct Harness {
+    pub app: App,
+    pub editor: Connection<Request, Response>,
+    /// what the plugin greeted the editor with
+    pub hello: Hello,
+    next_id: u64,
+}
+
+impl Harness {
+    /// Loads and saves scenes in `asset_root`.
+    pub fn new(asset_root: PathBuf) -> Self {
+        Self::with_plugin(asset_root, |_| {})
+    }
+
+    pub fn with_plugin(asset_root: PathBuf, configure: impl FnOnce(&mut EditorPlugin)) -> Self {
+        let (editor, runtime) = MemoryTransport::pair();
+        // answered ahead of time, the plugin waits for it while it's being built
+        transport::send_message::<HandshakeReply>(&editor, &Ok(Hello::editor(Capabilities::NONE)))
+            .unwrap();
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: asset_root.to_string_lossy().into_owned(),
+                ..default()
+            },
+            HierarchyPlugin,
+            TransformPlugin,
+            ScenePlugin,
+            // what the editor camera is moved with
+            InputPlugin,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+        ))
+        // what the editor's own scene is made of
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>();
+
+        let mut plugin = EditorPlugin::with_transport(Arc::new(runtime));
+        configure(&mut plugin);
+        app.add_plugins(plugin);
+
+        let hello = transport::recv_message::<Hello>(&editor).unwrap();
+        assert!(hello.capabilities.contains(Capabilities::ENTITIES));
+
+        let mut harness = Self {
+            app,
+            editor: Connection::new(Arc::new(editor)),
+            hello,
+            next_id: 0,
+        };
+        // enters `EditorState::Editor`
+        harness.app.update();
+        harness
+    }
+
+    pub fn send(&mut self, msg: EditorToRuntimeMsg) -> RequestId {
+        self.next_id += 1;
+        let id = RequestId(self.next_id);
+        self.editor.send(&Request { id, msg }).unwrap();
+        id
+    }
+
+    /// Updates the app until `condition` holds.
+    pub fn update_until(&mut self, what: &str, mut condition: impl FnMut(&mut App) -> bool) {
+        let started = Instant::now();
+        while !condition(&mut self.app) {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "timed out waiting for {}",
+                what
+            );
+            self.app.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Updates the app until the runtime sent something.
+    pub fn recv(&mut self) -> Response {
+        let editor = self.editor.clone();
+        let mut response = None;
+        self.update_until("a response", |_| {
+            response = editor.try_recv().unwrap();
+            response.is_some()
+        });
+        response.unwrap()
+    }
+
+    /// Sends `msg` and returns the answer to it, skipping anything the runtime sent unprompted.
+    pub fn request(&mut self, msg: EditorToRuntimeMsg) -> RuntimeToEditorMsg {
+        let id = self.send(msg);
+        loop {
+            let response = self.recv();
+            if response.request_id == Some(id) {
+                return response.msg;
+            }
+        }
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+}
+
+/// An empty directory to use as the asset folder.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "roth-editor-plugin-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    path
+}
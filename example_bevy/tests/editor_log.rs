@@ -0,0 +1,56 @@
+mod common;
+
+use bevy::{
+    log::tracing_subscriber::Registry,
+    prelude::*,
+    utils::tracing::{self, subscriber},
+};
+use common::{temp_dir, Harness};
+use example_bevy::editor_log::forward_logs;
+use roth_shared::{handshake::Capabilities, LogLevel, RuntimeToEditorMsg};
+
+// in its own test binary, the records are queued globally and any other app running the editor
+// plugin would send them instead
+#[test]
+fn forwards_log_records() {
+    let subscriber = forward_logs(Box::new(Registry::default()));
+    let mut harness = Harness::new(temp_dir("log"));
+    assert!(harness.hello.capabilities.contains(Capabilities::LOGS));
+
+    subscriber::with_default(subscriber, || {
+        info!(answer = 42, "hello from the runtime");
+        tracing::warn!(target: "game", "careful");
+    });
+
+    let mut records = vec![];
+    while records.len() < 2 {
+        if let RuntimeToEditorMsg::Log {
+            level,
+            target,
+            message,
+            fields,
+            ..
+        } = harness.recv().msg
+        {
+            records.push((level, target, message, fields));
+        }
+    }
+
+    assert_eq!(
+        records,
+        vec![
+            (
+                LogLevel::Info,
+                "editor_log".to_string(),
+                "hello from the runtime".to_string(),
+                vec![("answer".to_string(), "42".to_string())]
+            ),
+            (
+                LogLevel::Warn,
+                "game".to_string(),
+                "careful".to_string(),
+                vec![]
+            ),
+        ]
+    );
+}
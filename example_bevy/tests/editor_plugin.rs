@@ -0,0 +1,957 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{
+    app::AppExit,
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use common::{temp_dir, Harness};
+use example_bevy::{
+    editor_camera::EditorCamera,
+    editor_plugin::{EditorMarker, EditorPlugin, EditorState},
+};
+use roth_shared::{
+    handshake::Capabilities, ComponentData, EditorToRuntimeMsg, ErrorKind, LoadMode, PlayState,
+    ReflectValue, RuntimeToEditorMsg, SceneLoadStage,
+};
+
+fn names(world: &mut World) -> Vec<String> {
+    let mut names = world
+        .query_filtered::<&Name, Without<EditorMarker>>()
+        .iter(world)
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn name_component(name: &str) -> ComponentData {
+    ComponentData {
+        type_name: std::any::type_name::<Name>().to_string(),
+        value: Some(ReflectValue::String(name.to_string())),
+    }
+}
+
+fn transform_component(world: &World, transform: Transform) -> ComponentData {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    ComponentData {
+        type_name: std::any::type_name::<Transform>().to_string(),
+        value: Some(ReflectValue::from_reflect(&transform, &type_registry).unwrap()),
+    }
+}
+
+#[test]
+fn get_entities_includes_the_editor_scene() {
+    let mut harness = Harness::new(temp_dir("entities"));
+    let player = harness.world().spawn(Name::new("Player")).id();
+
+    let RuntimeToEditorMsg::Entities { entities, .. } =
+        harness.request(EditorToRuntimeMsg::GetEntities)
+    else {
+        panic!("expected entities");
+    };
+    let (_, components) = entities
+        .iter()
+        .find(|(entity, _)| *entity == player)
+        .expect("the player is missing");
+    assert!(components.contains(&name_component("Player")));
+
+    let editor_entities = entities
+        .iter()
+        .filter(|(_, components)| {
+            components
+                .iter()
+                .any(|it| it.type_name == std::any::type_name::<EditorMarker>())
+        })
+        .count();
+    assert_eq!(editor_entities, 4);
+}
+
+#[test]
+fn hierarchy_is_sent_with_the_entities() {
+    let mut harness = Harness::new(temp_dir("hierarchy"));
+    let mut children = vec![];
+    let parent = harness
+        .world()
+        .spawn(Name::new("Parent"))
+        .with_children(|parent| {
+            children.push(parent.spawn(Name::new("First")).id());
+            children.push(parent.spawn(Name::new("Second")).id());
+        })
+        .id();
+
+    let RuntimeToEditorMsg::Entities { hierarchy, .. } =
+        harness.request(EditorToRuntimeMsg::GetEntities)
+    else {
+        panic!("expected entities");
+    };
+    assert!(hierarchy.contains(&(parent, children.clone())));
+
+    // and kept up to date with diffs
+    harness.world().entity_mut(children[0]).remove_parent();
+    let RuntimeToEditorMsg::EntitiesChanged {
+        hierarchy_changed, ..
+    } = harness.recv().msg
+    else {
+        panic!("expected a diff");
+    };
+    assert_eq!(hierarchy_changed, vec![(parent, vec![children[1]])]);
+}
+
+#[test]
+fn set_parent_keeps_the_global_transform() {
+    let mut harness = Harness::new(temp_dir("set-parent"));
+    let parent = harness
+        .world()
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            10.0, 0.0, 0.0,
+        )))
+        .id();
+    let child = harness
+        .world()
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            1.0, 2.0, 3.0,
+        )))
+        .id();
+    // the `GlobalTransform`s are propagated at the end of the frame
+    harness.app.update();
+
+    let answer = harness.request(EditorToRuntimeMsg::SetParent {
+        child,
+        parent: Some(parent),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::ParentSet { child: moved, parent: Some(new_parent) }
+            if moved == child && new_parent == parent
+    ));
+    assert_eq!(
+        harness.world().get::<Parent>(child).map(|it| it.get()),
+        Some(parent)
+    );
+    assert_eq!(
+        harness.world().get::<Transform>(child).unwrap().translation,
+        Vec3::new(-9.0, 2.0, 3.0)
+    );
+    harness.app.update();
+    assert_eq!(
+        harness
+            .world()
+            .get::<GlobalTransform>(child)
+            .unwrap()
+            .translation(),
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+
+    // a parent can't become a child of its own child
+    let answer = harness.request(EditorToRuntimeMsg::SetParent {
+        child: parent,
+        parent: Some(child),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::InvalidValue,
+            ..
+        }
+    ));
+
+    harness.request(EditorToRuntimeMsg::SetParent {
+        child,
+        parent: None,
+    });
+    assert!(harness.world().get::<Parent>(child).is_none());
+    assert_eq!(
+        harness.world().get::<Transform>(child).unwrap().translation,
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+}
+
+#[test]
+fn insert_component() {
+    let mut harness = Harness::new(temp_dir("insert"));
+    let player = harness.world().spawn(Name::new("Player")).id();
+    harness.request(EditorToRuntimeMsg::GetEntities);
+
+    let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+    let component = transform_component(harness.world(), transform);
+    let answer = harness.request(EditorToRuntimeMsg::InsertComponent {
+        entity: player,
+        component: component.clone(),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::ComponentInserted { entity, .. } if entity == player
+    ));
+    assert_eq!(harness.world().get::<Transform>(player), Some(&transform));
+
+    // the change makes it back to the editor as a diff
+    let RuntimeToEditorMsg::EntitiesChanged {
+        components_changed, ..
+    } = harness.recv().msg
+    else {
+        panic!("expected a diff");
+    };
+    assert_eq!(components_changed, vec![(player, vec![component])]);
+}
+
+#[test]
+fn insert_existing_component_updates_it() {
+    let mut harness = Harness::new(temp_dir("insert-existing"));
+    let player = harness
+        .world()
+        .spawn((Name::new("Player"), Transform::from_xyz(1.0, 2.0, 3.0)))
+        .id();
+
+    let transform = Transform::from_xyz(4.0, 5.0, 6.0);
+    let component = transform_component(harness.world(), transform);
+    let answer = harness.request(EditorToRuntimeMsg::InsertComponent {
+        entity: player,
+        component,
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::ComponentInserted { entity, .. } if entity == player
+    ));
+    assert_eq!(harness.world().get::<Transform>(player), Some(&transform));
+}
+
+#[test]
+fn insert_component_failures_are_answered() {
+    let mut harness = Harness::new(temp_dir("insert-failed"));
+    let player = harness.world().spawn(Name::new("Player")).id();
+
+    let answer = harness.request(EditorToRuntimeMsg::InsertComponent {
+        entity: player,
+        component: ComponentData {
+            type_name: "game::Unregistered".to_string(),
+            value: Some(ReflectValue::Unit),
+        },
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::NotRegistered,
+            ..
+        }
+    ));
+
+    // a value that doesn't fit the type
+    let answer = harness.request(EditorToRuntimeMsg::InsertComponent {
+        entity: player,
+        component: ComponentData {
+            type_name: std::any::type_name::<Transform>().to_string(),
+            value: Some(ReflectValue::String("Player".to_string())),
+        },
+    });
+    let RuntimeToEditorMsg::Error {
+        request_id,
+        kind,
+        message,
+    } = answer
+    else {
+        panic!("expected the insert to fail, got {:?}", answer);
+    };
+    assert!(request_id.is_some());
+    assert_eq!(kind, ErrorKind::InvalidValue);
+    assert!(message.contains(std::any::type_name::<Transform>()));
+    assert!(harness.world().get::<Transform>(player).is_none());
+}
+
+#[test]
+fn handles_everything_sent_since_the_last_frame() {
+    let mut harness = Harness::new(temp_dir("drain"));
+    for name in ["First", "Second", "Third"] {
+        harness.send(EditorToRuntimeMsg::SpawnEntity {
+            components: vec![name_component(name)],
+        });
+    }
+
+    harness.app.update();
+    assert_eq!(names(harness.world()), vec!["First", "Second", "Third"]);
+}
+
+#[test]
+fn spawn_entity() {
+    let mut harness = Harness::new(temp_dir("spawn"));
+    let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+    let components = vec![
+        name_component("Player"),
+        transform_component(harness.world(), transform),
+    ];
+
+    let answer = harness.request(EditorToRuntimeMsg::SpawnEntity { components });
+    let RuntimeToEditorMsg::EntitySpawned { entity } = answer else {
+        panic!("expected the entity to be spawned, got {:?}", answer);
+    };
+    assert_eq!(
+        harness.world().get::<Name>(entity).map(|it| it.as_str()),
+        Some("Player")
+    );
+    assert_eq!(harness.world().get::<Transform>(entity), Some(&transform));
+}
+
+#[test]
+fn despawn_entity() {
+    let mut harness = Harness::new(temp_dir("despawn"));
+    let mut child = Entity::PLACEHOLDER;
+    let parent = harness
+        .world()
+        .spawn(Name::new("Parent"))
+        .with_children(|parent| {
+            child = parent.spawn(Name::new("Child")).id();
+        })
+        .id();
+
+    let answer = harness.request(EditorToRuntimeMsg::DespawnEntity {
+        entity: parent,
+        recursive: false,
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::EntityDespawned { entity } if entity == parent
+    ));
+    // left behind without a parent
+    assert!(harness.world().get_entity(parent).is_none());
+    assert!(harness.world().get::<Parent>(child).is_none());
+
+    let grandparent = harness.world().spawn(Name::new("Grandparent")).id();
+    harness.world().entity_mut(grandparent).add_child(child);
+    harness.request(EditorToRuntimeMsg::DespawnEntity {
+        entity: grandparent,
+        recursive: true,
+    });
+    assert!(harness.world().get_entity(child).is_none());
+
+    // despawned already
+    let answer = harness.request(EditorToRuntimeMsg::DespawnEntity {
+        entity: parent,
+        recursive: true,
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::NotFound,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn duplicate_entity() {
+    let mut harness = Harness::new(temp_dir("duplicate"));
+    let mut lamp = Entity::PLACEHOLDER;
+    let room = harness
+        .world()
+        .spawn(Name::new("Room"))
+        .with_children(|room| {
+            lamp = room
+                .spawn((Name::new("Lamp"), Transform::from_xyz(1.0, 2.0, 3.0)))
+                .with_children(|lamp| {
+                    lamp.spawn(Name::new("Bulb"));
+                })
+                .id();
+        })
+        .id();
+
+    let RuntimeToEditorMsg::EntityDuplicated { entity, spawned } =
+        harness.request(EditorToRuntimeMsg::DuplicateEntity {
+            entity: lamp,
+            recursive: true,
+        })
+    else {
+        panic!("expected the lamp to be duplicated");
+    };
+    assert_eq!(spawned.len(), 2);
+    assert_eq!(spawned[0], entity);
+    let world = harness.world();
+    assert_eq!(world.get::<Name>(entity).unwrap().as_str(), "Lamp");
+    assert_eq!(
+        world.get::<Transform>(entity).unwrap().translation,
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+    // next to the original, with a copy of the bulb rather than the bulb itself
+    assert_eq!(world.get::<Parent>(entity).unwrap().get(), room);
+    assert_eq!(world.get::<Children>(room).unwrap().len(), 2);
+    assert_eq!(
+        world.get::<Children>(entity).unwrap().to_vec(),
+        vec![spawned[1]]
+    );
+    assert_eq!(world.get::<Name>(spawned[1]).unwrap().as_str(), "Bulb");
+    assert_eq!(world.get::<Children>(lamp).unwrap().len(), 1);
+
+    let RuntimeToEditorMsg::EntityDuplicated { entity, spawned } =
+        harness.request(EditorToRuntimeMsg::DuplicateEntity {
+            entity: lamp,
+            recursive: false,
+        })
+    else {
+        panic!("expected the lamp to be duplicated");
+    };
+    assert_eq!(spawned, vec![entity]);
+    assert!(harness.world().get::<Children>(entity).is_none());
+
+    let answer = harness.request(EditorToRuntimeMsg::DuplicateEntity {
+        entity: Entity::from_raw(9999),
+        recursive: true,
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::NotFound,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn remove_component() {
+    let mut harness = Harness::new(temp_dir("remove"));
+    let player = harness
+        .world()
+        .spawn((Name::new("Player"), Transform::default()))
+        .id();
+
+    let answer = harness.request(EditorToRuntimeMsg::RemoveComponent {
+        entity: player,
+        type_name: std::any::type_name::<Transform>().to_string(),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::ComponentRemoved { entity, .. } if entity == player
+    ));
+    assert!(harness.world().get::<Transform>(player).is_none());
+    assert!(harness.world().get::<Name>(player).is_some());
+}
+
+#[test]
+fn set_field_leaves_the_other_fields_alone() {
+    let mut harness = Harness::new(temp_dir("set-field"));
+    let player = harness
+        .world()
+        .spawn(Transform::from_xyz(1.0, 2.0, 3.0))
+        .id();
+    // changed by the game after the editor last saw the transform
+    harness
+        .world()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .y = 7.0;
+
+    let answer = harness.request(EditorToRuntimeMsg::SetField {
+        entity: player,
+        component: std::any::type_name::<Transform>().to_string(),
+        path: "translation.x".to_string(),
+        value: ReflectValue::F32(5.0),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::FieldSet { entity, ref path, .. }
+            if entity == player && path == "translation.x"
+    ));
+    assert_eq!(
+        harness
+            .world()
+            .get::<Transform>(player)
+            .unwrap()
+            .translation,
+        Vec3::new(5.0, 7.0, 3.0)
+    );
+
+    // a value of the wrong type isn't applied, and doesn't take the runtime down with it
+    let answer = harness.request(EditorToRuntimeMsg::SetField {
+        entity: player,
+        component: std::any::type_name::<Transform>().to_string(),
+        path: "translation.x".to_string(),
+        value: ReflectValue::String("5".to_string()),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::InvalidValue,
+            ..
+        }
+    ));
+    let answer = harness.request(EditorToRuntimeMsg::SetField {
+        entity: player,
+        component: std::any::type_name::<Transform>().to_string(),
+        path: "translation.w".to_string(),
+        value: ReflectValue::F32(5.0),
+    });
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::InvalidPath,
+            ..
+        }
+    ));
+    assert_eq!(
+        harness
+            .world()
+            .get::<Transform>(player)
+            .unwrap()
+            .translation,
+        Vec3::new(5.0, 7.0, 3.0)
+    );
+}
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+struct Gravity {
+    direction: Vec3,
+    strength: f32,
+}
+
+#[test]
+fn set_resource_field() {
+    let mut harness = Harness::new(temp_dir("set-resource-field"));
+    harness
+        .world()
+        .resource::<AppTypeRegistry>()
+        .write()
+        .register::<Gravity>();
+    harness.world().insert_resource(Gravity {
+        direction: Vec3::NEG_Y,
+        strength: 9.81,
+    });
+
+    let answer = harness.request(EditorToRuntimeMsg::SetResourceField {
+        resource: std::any::type_name::<Gravity>().to_string(),
+        path: "strength".to_string(),
+        value: ReflectValue::F32(1.62),
+    });
+    let RuntimeToEditorMsg::ResourceSet { resource } = answer else {
+        panic!("expected the resource, got {:?}", answer);
+    };
+    assert_eq!(
+        resource.value.as_ref().and_then(|it| it.field("strength")),
+        Some(&ReflectValue::F32(1.62))
+    );
+    assert_eq!(harness.world().resource::<Gravity>().strength, 1.62);
+    assert_eq!(harness.world().resource::<Gravity>().direction, Vec3::NEG_Y);
+}
+
+#[test]
+fn save_and_load_scene() {
+    let asset_root = temp_dir("save");
+    let mut harness = Harness::new(asset_root.clone());
+    harness
+        .world()
+        .spawn((Name::new("Player"), Transform::default()));
+    harness.world().spawn(Name::new("Enemy"));
+
+    let saved = harness.request(EditorToRuntimeMsg::Save {
+        path: "level.scn.ron".to_string(),
+    });
+    let RuntimeToEditorMsg::Saved { path, bytes } = saved else {
+        panic!("expected the scene to be saved, got {:?}", saved);
+    };
+    assert_eq!(path, "level.scn.ron");
+
+    let ron = std::fs::read_to_string(asset_root.join("level.scn.ron")).unwrap();
+    assert_eq!(ron.len(), bytes);
+    assert!(ron.contains("\"Player\""));
+    // the editor's own entities aren't part of the scene
+    assert!(!ron.contains("EditorMarker"));
+
+    // relative to the same folder the scene was saved to
+    let loaded = harness.request(EditorToRuntimeMsg::LoadScene {
+        path,
+        mode: LoadMode::Additive,
+    });
+    std::fs::remove_dir_all(&asset_root).unwrap();
+
+    assert!(
+        matches!(loaded, RuntimeToEditorMsg::SceneLoaded { entities: 2, .. }),
+        "expected the scene to be spawned, got {:?}",
+        loaded
+    );
+    assert_eq!(
+        names(harness.world()),
+        vec!["Enemy", "Enemy", "Player", "Player"]
+    );
+}
+
+#[test]
+fn stop_restores_the_world_from_before_play() {
+    let asset_root = temp_dir("play");
+    let mut harness = Harness::new(asset_root.clone());
+    harness.app.register_type::<Visibility>();
+    let player = harness
+        .world()
+        .spawn((Name::new("Player"), Transform::from_xyz(1.0, 0.0, 0.0)))
+        .id();
+
+    let answer = harness.request(EditorToRuntimeMsg::Pause);
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::InvalidState,
+            ..
+        }
+    ));
+
+    let answer = harness.request(EditorToRuntimeMsg::Play);
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::PlayStateChanged {
+            state: PlayState::Playing
+        }
+    ));
+    harness.update_until("the game to start", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Game
+    });
+    harness
+        .world()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .x = 5.0;
+    harness
+        .world()
+        .entity_mut(player)
+        .insert(Visibility::Hidden);
+    harness.world().spawn(Name::new("Bullet"));
+
+    let answer = harness.request(EditorToRuntimeMsg::Pause);
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::PlayStateChanged {
+            state: PlayState::Paused
+        }
+    ));
+    assert!(harness.world().resource::<Time<Virtual>>().is_paused());
+
+    // what's played doesn't end up in the scene
+    harness.request(EditorToRuntimeMsg::Save {
+        path: "level.scn.ron".to_string(),
+    });
+    let ron = std::fs::read_to_string(asset_root.join("level.scn.ron")).unwrap();
+    std::fs::remove_dir_all(&asset_root).unwrap();
+    assert!(ron.contains("\"Player\""));
+    assert!(!ron.contains("Bullet"));
+
+    let answer = harness.request(EditorToRuntimeMsg::Stop);
+    assert!(matches!(
+        answer,
+        RuntimeToEditorMsg::PlayStateChanged {
+            state: PlayState::Editing
+        }
+    ));
+    harness.update_until("the editor to be back", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Editor
+    });
+    assert!(!harness.world().resource::<Time<Virtual>>().is_paused());
+    assert_eq!(names(harness.world()), vec!["Player"]);
+    // the same entity as before, as it was before
+    assert_eq!(
+        harness
+            .world()
+            .get::<Transform>(player)
+            .unwrap()
+            .translation
+            .x,
+        1.0
+    );
+    assert!(harness.world().get::<Visibility>(player).is_none());
+}
+
+/// Not registered, so no scene can hold it.
+#[derive(Component, Debug, PartialEq)]
+struct Unreflected(u32);
+
+#[test]
+fn stop_keeps_what_the_snapshot_cant_hold() {
+    let mut harness = Harness::new(temp_dir("play-unreflected"));
+    // the render plugins aren't added, it's only removed on `Stop` when it's reflected
+    harness.app.register_type::<Visibility>();
+    let player = harness
+        .world()
+        .spawn((
+            Name::new("Player"),
+            Transform::from_xyz(1.0, 0.0, 0.0),
+            Unreflected(7),
+        ))
+        .id();
+    // e.g. one a plugin spawned for itself
+    let internal = harness.world().spawn(Unreflected(1)).id();
+
+    harness.request(EditorToRuntimeMsg::Play);
+    harness.update_until("the game to start", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Game
+    });
+    harness
+        .world()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .x = 5.0;
+    harness
+        .world()
+        .entity_mut(player)
+        .insert(Visibility::Hidden)
+        .with_children(|parent| {
+            parent.spawn(Name::new("Bullet"));
+        });
+
+    harness.request(EditorToRuntimeMsg::Stop);
+    harness.update_until("the editor to be back", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Editor
+    });
+
+    let player_ref = harness.world().entity(player);
+    assert_eq!(player_ref.get::<Unreflected>(), Some(&Unreflected(7)));
+    assert_eq!(player_ref.get::<Transform>().unwrap().translation.x, 1.0);
+    assert!(player_ref.get::<Visibility>().is_none());
+    assert!(player_ref.get::<Children>().is_none());
+    assert_eq!(
+        harness.world().get::<Unreflected>(internal),
+        Some(&Unreflected(1))
+    );
+    assert_eq!(names(harness.world()), vec!["Player"]);
+}
+
+#[test]
+fn game_cameras_only_render_while_playing() {
+    let mut harness = Harness::new(temp_dir("cameras"));
+    // the render plugins aren't added, nothing else needs it
+    harness.app.register_type::<Camera>();
+    let camera = harness
+        .world()
+        .spawn((Name::new("Game camera"), Camera::default()))
+        .id();
+    let is_active =
+        |harness: &mut Harness| harness.world().get::<Camera>(camera).unwrap().is_active;
+
+    harness.app.update();
+    assert!(!is_active(&mut harness));
+    let editor_cameras = harness
+        .world()
+        .query::<(&Camera, &EditorCamera)>()
+        .iter(harness.world())
+        .filter(|(camera, _)| camera.is_active)
+        .count();
+    assert_eq!(editor_cameras, 1);
+
+    harness.request(EditorToRuntimeMsg::Play);
+    harness.update_until("the game to start", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Game
+    });
+    assert!(is_active(&mut harness));
+    let editor_cameras = harness
+        .world()
+        .query::<&EditorCamera>()
+        .iter(harness.world())
+        .count();
+    assert_eq!(editor_cameras, 0);
+
+    harness.request(EditorToRuntimeMsg::Stop);
+    harness.update_until("the editor to be back", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Editor
+    });
+    harness.app.update();
+    assert!(!is_active(&mut harness));
+
+    // the world from before playing has it switched on, not the way it was while editing
+    harness.request(EditorToRuntimeMsg::Play);
+    harness.update_until("the game to start", |app| {
+        app.world.resource::<State<EditorState>>().get() == &EditorState::Game
+    });
+    assert!(is_active(&mut harness));
+}
+
+#[test]
+fn scrolling_zooms_the_editor_camera_in() {
+    let mut harness = Harness::new(temp_dir("zoom"));
+    let distance = |harness: &mut Harness| {
+        let (transform, camera) = harness
+            .world()
+            .query::<(&Transform, &EditorCamera)>()
+            .single(harness.world());
+        transform.translation.distance(camera.focus)
+    };
+    let before = distance(&mut harness);
+
+    harness.world().send_event(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: 0.0,
+        y: 2.0,
+        window: Entity::PLACEHOLDER,
+    });
+    harness.app.update();
+
+    let after = distance(&mut harness);
+    assert!(
+        (after - before * 0.81).abs() < 0.001,
+        "{} -> {}",
+        before,
+        after
+    );
+}
+
+#[test]
+fn load_scene_replaces_everything_but_the_editor() {
+    let asset_root = temp_dir("replace");
+    let mut harness = Harness::new(asset_root.clone());
+    let editor_entities = harness
+        .world()
+        .query_filtered::<(), With<EditorMarker>>()
+        .iter(harness.world())
+        .count();
+    harness.world().spawn(Name::new("Player"));
+    harness.request(EditorToRuntimeMsg::Save {
+        path: "level.scn.ron".to_string(),
+    });
+    harness
+        .world()
+        .spawn(Name::new("Enemy"))
+        .with_children(|parent| {
+            parent.spawn(Name::new("Weapon"));
+        });
+
+    let id = harness.send(EditorToRuntimeMsg::LoadScene {
+        path: "level.scn.ron".to_string(),
+        mode: LoadMode::Replace,
+    });
+    let mut stages = vec![];
+    let loaded = loop {
+        let response = harness.recv();
+        match response.msg {
+            RuntimeToEditorMsg::SceneLoading { stage, .. } => stages.push(stage),
+            msg if response.request_id == Some(id) => break msg,
+            _ => {}
+        }
+    };
+    std::fs::remove_dir_all(&asset_root).unwrap();
+
+    assert!(
+        matches!(loaded, RuntimeToEditorMsg::SceneLoaded { entities: 1, .. }),
+        "expected the scene to be spawned, got {:?}",
+        loaded
+    );
+    assert_eq!(stages.first(), Some(&SceneLoadStage::Loading));
+    assert_eq!(names(harness.world()), vec!["Player"]);
+    assert_eq!(
+        harness
+            .world()
+            .query_filtered::<(), With<EditorMarker>>()
+            .iter(harness.world())
+            .count(),
+        editor_entities
+    );
+}
+
+#[test]
+fn load_missing_scene_fails() {
+    let asset_root = temp_dir("load-missing");
+    let mut harness = Harness::new(asset_root.clone());
+    harness.world().spawn(Name::new("Player"));
+
+    let loaded = harness.request(EditorToRuntimeMsg::LoadScene {
+        path: "missing.scn.ron".to_string(),
+        mode: LoadMode::Replace,
+    });
+    std::fs::remove_dir_all(&asset_root).unwrap();
+
+    assert!(
+        matches!(&loaded, RuntimeToEditorMsg::SceneLoadFailed { path, .. } if path == "missing.scn.ron"),
+        "expected the load to fail, got {:?}",
+        loaded
+    );
+    // nothing is replaced by a scene that doesn't exist
+    assert_eq!(names(harness.world()), vec!["Player"]);
+}
+
+#[test]
+fn save_outside_of_the_asset_folder_fails() {
+    let asset_root = temp_dir("save-outside");
+    let mut harness = Harness::new(asset_root.clone());
+
+    let saved = harness.request(EditorToRuntimeMsg::Save {
+        path: "../level.scn.ron".to_string(),
+    });
+    std::fs::remove_dir_all(&asset_root).unwrap();
+
+    assert!(
+        matches!(&saved, RuntimeToEditorMsg::SaveFailed { path, .. } if path == "../level.scn.ron"),
+        "expected the save to fail, got {:?}",
+        saved
+    );
+}
+
+#[test]
+fn input_without_a_window_is_ignored() {
+    let mut harness = Harness::new(temp_dir("input"));
+
+    harness.send(EditorToRuntimeMsg::CursorEntered);
+    harness.send(EditorToRuntimeMsg::Shutdown);
+    harness.update_until("the app to exit", |app| {
+        !app.world.resource::<Events<AppExit>>().is_empty()
+    });
+}
+
+#[test]
+fn streams_diagnostics() {
+    let mut harness = Harness::with_plugin(temp_dir("diagnostics"), |plugin| {
+        plugin.diagnostics_interval = Some(Duration::from_millis(1));
+    });
+    assert!(harness
+        .hello
+        .capabilities
+        .contains(Capabilities::DIAGNOSTICS));
+    harness.app.register_asset_reflect::<Mesh>();
+
+    let expected = ["assets/Mesh", "entity_count", "fps", "frame_time"];
+    let diagnostics = loop {
+        if let RuntimeToEditorMsg::Diagnostics { diagnostics } = harness.recv().msg {
+            if expected
+                .iter()
+                .all(|path| diagnostics.iter().any(|it| it.path == *path))
+            {
+                break diagnostics;
+            }
+        }
+    };
+
+    let value = |path: &str| diagnostics.iter().find(|it| it.path == path).unwrap();
+    // the editor's own scene has a circle and a cube
+    assert_eq!(value("assets/Mesh").value, 2.0);
+    assert_eq!(value("frame_time").suffix, "ms");
+}
+
+#[test]
+fn sends_heartbeats() {
+    let mut harness = Harness::new(temp_dir("heartbeat"));
+    assert!(harness.hello.capabilities.contains(Capabilities::HEARTBEAT));
+
+    while !matches!(harness.recv().msg, RuntimeToEditorMsg::Heartbeat) {}
+}
+
+#[test]
+fn keeps_running_when_the_editor_is_gone() {
+    let mut harness = Harness::new(temp_dir("editor-gone"));
+    harness.send(EditorToRuntimeMsg::GetEntities);
+    let Harness {
+        mut app, editor, ..
+    } = harness;
+    drop(editor);
+
+    // answering the request fails, and so does sending the diff for the new entity
+    app.update();
+    app.world.spawn(Name::new("Player"));
+    app.update();
+    assert_eq!(names(&mut app.world), vec!["Player".to_string()]);
+}
+
+#[test]
+fn runs_standalone_without_an_editor() {
+    // the test binary is started without any --roth-* flags
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, EditorPlugin::default()));
+    app.update();
+
+    assert!(app.world.get_resource::<State<EditorState>>().is_none());
+}
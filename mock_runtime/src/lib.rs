@@ -0,0 +1,771 @@
+//! A runtime that speaks the `roth_shared` protocol without bevy's app, a window or a GPU, so
+//! the editor can be developed and tested headlessly. Entities and resources come from a scene
+//! `.ron` file, without a type registry the values are only as precise as RON itself: numbers
+//! become `i64`/`f64` and enum variant names are lost.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::ecs::entity::Entity;
+use roth_shared::{
+    handshake::{Capabilities, HandshakeError, HandshakeReply, Hello, RuntimeInfo},
+    resolve_asset_path,
+    ron::{self, value::Float, Map, Number, Value},
+    transport::{self, Connection, Transport, TransportError},
+    value::VariantValue,
+    ComponentData, EditorToRuntimeMsg, ErrorKind, LoadMode, PlayState, ReflectValue, Request,
+    Response, RuntimeToEditorMsg, TypeRegistrySchema,
+};
+
+#[derive(Debug)]
+pub enum MockError {
+    Io(std::io::Error),
+    Ron(String),
+    /// The file parsed, but isn't shaped like a scene.
+    NotAScene(String),
+    Transport(TransportError),
+    Handshake(HandshakeError),
+}
+
+impl fmt::Display for MockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MockError::Io(err) => write!(f, "{}", err),
+            MockError::Ron(err) => write!(f, "invalid ron: {}", err),
+            MockError::NotAScene(err) => write!(f, "not a scene: {}", err),
+            MockError::Transport(err) => write!(f, "{}", err),
+            MockError::Handshake(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for MockError {}
+
+impl From<std::io::Error> for MockError {
+    fn from(err: std::io::Error) -> Self {
+        MockError::Io(err)
+    }
+}
+
+impl From<TransportError> for MockError {
+    fn from(err: TransportError) -> Self {
+        MockError::Transport(err)
+    }
+}
+
+/// Entities and resources of a scene file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockScene {
+    pub entities: BTreeMap<Entity, Vec<ComponentData>>,
+    pub resources: Vec<ComponentData>,
+}
+
+impl MockScene {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MockError> {
+        Self::from_ron(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, MockError> {
+        let value: Value = ron::from_str(ron).map_err(|err| MockError::Ron(err.to_string()))?;
+        let Value::Map(mut scene) = value else {
+            return Err(MockError::NotAScene(
+                "expected `(resources: .., entities: ..)`".into(),
+            ));
+        };
+
+        let resources = match scene.remove(&Value::String("resources".into())) {
+            Some(resources) => components_from_ron(resources)?,
+            None => vec![],
+        };
+
+        let mut entities = BTreeMap::new();
+        if let Some(Value::Map(entity_map)) = scene.remove(&Value::String("entities".into())) {
+            for (id, entity) in entity_map {
+                let Value::Number(Number::Integer(id)) = id else {
+                    return Err(MockError::NotAScene(format!("{:?} is not an entity", id)));
+                };
+                let components = match entity {
+                    Value::Map(mut entity) => entity
+                        .remove(&Value::String("components".into()))
+                        .map(components_from_ron)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    _ => vec![],
+                };
+                let entity = Entity::try_from_bits(id as u64)
+                    .map_err(|_| MockError::NotAScene(format!("{} is not an entity", id)))?;
+                entities.insert(entity, components);
+            }
+        }
+
+        Ok(Self {
+            entities,
+            resources,
+        })
+    }
+
+    /// Writes the scene in the same shape it was read in.
+    pub fn to_ron(&self) -> Result<String, String> {
+        let mut entities = Map::new();
+        for (entity, components) in &self.entities {
+            let mut entity_map = Map::new();
+            entity_map.insert(
+                Value::String("components".into()),
+                components_to_ron(components)?,
+            );
+            entities.insert(
+                Value::Number(Number::Integer(entity.to_bits() as i64)),
+                Value::Map(entity_map),
+            );
+        }
+
+        let mut scene = Map::new();
+        scene.insert(
+            Value::String("resources".into()),
+            components_to_ron(&self.resources)?,
+        );
+        scene.insert(Value::String("entities".into()), Value::Map(entities));
+
+        Ok(
+            ron::ser::to_string_pretty(&Value::Map(scene), ron::ser::PrettyConfig::default())
+                .expect("ron values always serialize"),
+        )
+    }
+
+    /// The `EntitiesChanged` that turns `previous` into this scene.
+    pub fn changes_since(&self, previous: &MockScene) -> RuntimeToEditorMsg {
+        let mut spawned = vec![];
+        let mut components_changed = vec![];
+        let mut components_removed = vec![];
+        for (entity, components) in &self.entities {
+            let Some(previous_components) = previous.entities.get(entity) else {
+                spawned.push((*entity, components.clone()));
+                continue;
+            };
+            components_changed.push((*entity, components.clone()));
+            let removed = previous_components
+                .iter()
+                .filter(|previous| {
+                    components
+                        .iter()
+                        .all(|it| it.type_name != previous.type_name)
+                })
+                .map(|previous| previous.type_name.clone())
+                .collect::<Vec<_>>();
+            if !removed.is_empty() {
+                components_removed.push((*entity, removed));
+            }
+        }
+
+        RuntimeToEditorMsg::EntitiesChanged {
+            spawned,
+            despawned: previous
+                .entities
+                .keys()
+                .filter(|entity| !self.entities.contains_key(entity))
+                .copied()
+                .collect(),
+            components_changed,
+            components_removed,
+            hierarchy_changed: vec![],
+        }
+    }
+}
+
+fn components_from_ron(value: Value) -> Result<Vec<ComponentData>, MockError> {
+    let Value::Map(map) = value else {
+        return Err(MockError::NotAScene(format!(
+            "expected a map of type names, found {:?}",
+            value
+        )));
+    };
+
+    map.into_iter()
+        .map(|(type_name, value)| match type_name {
+            Value::String(type_name) => Ok(ComponentData {
+                type_name,
+                value: Some(from_ron(value)),
+            }),
+            _ => Err(MockError::NotAScene(format!(
+                "{:?} is not a type name",
+                type_name
+            ))),
+        })
+        .collect()
+}
+
+fn components_to_ron(components: &[ComponentData]) -> Result<Value, String> {
+    let mut map = Map::new();
+    for component in components {
+        let value = match &component.value {
+            Some(value) => to_ron(value)
+                .map_err(|err| format!("can't save {}: {}", component.type_name, err))?,
+            None => Value::Unit,
+        };
+        map.insert(Value::String(component.type_name.clone()), value);
+    }
+    Ok(Value::Map(map))
+}
+
+fn from_ron(value: Value) -> ReflectValue {
+    match value {
+        Value::Bool(value) => ReflectValue::Bool(value),
+        Value::Char(value) => ReflectValue::Char(value),
+        Value::String(value) => ReflectValue::String(value),
+        Value::Unit => ReflectValue::Unit,
+        Value::Number(Number::Integer(value)) => ReflectValue::I64(value),
+        Value::Number(Number::Float(value)) => ReflectValue::F64(value.get()),
+        Value::Option(value) => ReflectValue::Option(value.map(|it| Box::new(from_ron(*it)))),
+        Value::Seq(values) => ReflectValue::Seq(values.into_iter().map(from_ron).collect()),
+        Value::Map(map) => {
+            if map.keys().all(|key| matches!(key, Value::String(_))) {
+                ReflectValue::Struct(
+                    map.into_iter()
+                        .map(|(key, value)| match key {
+                            Value::String(key) => (key, from_ron(value)),
+                            _ => unreachable!(),
+                        })
+                        .collect(),
+                )
+            } else {
+                ReflectValue::Map(
+                    map.into_iter()
+                        .map(|(key, value)| (from_ron(key), from_ron(value)))
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+/// Fails for integers outside of `i64`, the only integer `ron::Value` has.
+fn to_ron(value: &ReflectValue) -> Result<Value, String> {
+    let integer = |value: i64| Value::Number(Number::Integer(value));
+    let wide_integer = |value: &dyn std::fmt::Display, fits: Result<i64, _>| {
+        fits.map(integer)
+            .map_err(|_: std::num::TryFromIntError| format!("{} doesn't fit in an i64", value))
+    };
+    let seq = |values: &[ReflectValue]| values.iter().map(to_ron).collect::<Result<_, _>>();
+    let struct_fields = |fields: &[(String, ReflectValue)]| {
+        fields
+            .iter()
+            .map(|(key, value)| Ok((Value::String(key.clone()), to_ron(value)?)))
+            .collect::<Result<_, String>>()
+    };
+    Ok(match value {
+        ReflectValue::Unit => Value::Unit,
+        ReflectValue::Bool(value) => Value::Bool(*value),
+        ReflectValue::I8(value) => integer(*value as i64),
+        ReflectValue::I16(value) => integer(*value as i64),
+        ReflectValue::I32(value) => integer(*value as i64),
+        ReflectValue::I64(value) => integer(*value),
+        ReflectValue::I128(value) => wide_integer(value, i64::try_from(*value))?,
+        ReflectValue::U8(value) => integer(*value as i64),
+        ReflectValue::U16(value) => integer(*value as i64),
+        ReflectValue::U32(value) => integer(*value as i64),
+        ReflectValue::U64(value) => wide_integer(value, i64::try_from(*value))?,
+        ReflectValue::U128(value) => wide_integer(value, i64::try_from(*value))?,
+        ReflectValue::F32(value) => Value::Number(Number::Float(Float::new(*value as f64))),
+        ReflectValue::F64(value) => Value::Number(Number::Float(Float::new(*value))),
+        ReflectValue::Char(value) => Value::Char(*value),
+        ReflectValue::String(value) => Value::String(value.clone()),
+        ReflectValue::Bytes(value) => {
+            Value::Seq(value.iter().map(|it| integer(*it as i64)).collect())
+        }
+        ReflectValue::Option(value) => Value::Option(match value {
+            Some(value) => Some(Box::new(to_ron(value)?)),
+            None => None,
+        }),
+        ReflectValue::Newtype(value) => to_ron(value)?,
+        ReflectValue::Seq(values) | ReflectValue::Tuple(values) => Value::Seq(seq(values)?),
+        ReflectValue::Map(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(key, value)| Ok((to_ron(key)?, to_ron(value)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        ReflectValue::Struct(fields) => Value::Map(struct_fields(fields)?),
+        ReflectValue::Enum { variant, value } => {
+            let fields = match value {
+                VariantValue::Unit => return Ok(Value::String(variant.clone())),
+                VariantValue::Newtype(value) => to_ron(value)?,
+                VariantValue::Tuple(values) => Value::Seq(seq(values)?),
+                VariantValue::Struct(fields) => Value::Map(struct_fields(fields)?),
+            };
+            // `ron::Value` has no named variants, it's read back as a struct with one field
+            let mut map = Map::new();
+            map.insert(Value::String(variant.clone()), fields);
+            Value::Map(map)
+        }
+    })
+}
+
+/// Answers requests from a [`MockScene`] instead of a bevy world.
+pub struct MockRuntime {
+    scene: MockScene,
+    asset_root: PathBuf,
+    /// the scene from before `Play` while playing, there is no game so it's only ever restored
+    play_snapshot: Option<MockScene>,
+    paused: bool,
+}
+
+impl MockRuntime {
+    /// `Save` and `LoadScene` paths are relative to `asset_root`.
+    pub fn new(scene: MockScene, asset_root: impl Into<PathBuf>) -> Self {
+        Self {
+            scene,
+            asset_root: asset_root.into(),
+            play_snapshot: None,
+            paused: false,
+        }
+    }
+
+    /// Index after the highest one in use, entities the mock spawns never reuse one.
+    fn next_index(&self) -> u32 {
+        self.scene
+            .entities
+            .keys()
+            .map(|entity| entity.index() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn save(&self, path: &str) -> Result<usize, String> {
+        let file_path = resolve_asset_path(&self.asset_root, path)?;
+        let ron = self
+            .play_snapshot
+            .as_ref()
+            .unwrap_or(&self.scene)
+            .to_ron()?;
+        std::fs::write(&file_path, &ron)
+            .map_err(|err| format!("error writing {}: {}", file_path.display(), err))?;
+        Ok(ron.len())
+    }
+
+    pub fn scene(&self) -> &MockScene {
+        &self.scene
+    }
+
+    pub fn hello() -> Hello {
+        Hello::runtime(
+            Capabilities::ENTITIES
+                | Capabilities::ENTITY_DIFFS
+                | Capabilities::SCENES
+                | Capabilities::TYPE_REGISTRY
+                | Capabilities::RESOURCES,
+            RuntimeInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        )
+    }
+
+    /// Everything to send back for `request`, the answer comes first.
+    pub fn handle(&mut self, Request { id, msg }: Request) -> Vec<Response> {
+        let answer = |msg| Response {
+            request_id: Some(id),
+            msg,
+        };
+        let error = |kind, message: String| {
+            log::error!("{}", message);
+            vec![answer(RuntimeToEditorMsg::Error {
+                request_id: Some(id),
+                kind,
+                message,
+            })]
+        };
+
+        match msg {
+            EditorToRuntimeMsg::GetEntities => vec![answer(RuntimeToEditorMsg::Entities {
+                entities: self
+                    .scene
+                    .entities
+                    .iter()
+                    .map(|(entity, components)| (*entity, components.clone()))
+                    .collect(),
+                hierarchy: vec![],
+            })],
+            EditorToRuntimeMsg::InsertComponent { entity, component } => {
+                let Some(components) = self.scene.entities.get_mut(&entity) else {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!(
+                            "can't insert {}, {:?} doesn't exist",
+                            component.type_name, entity
+                        ),
+                    );
+                };
+                match components
+                    .iter_mut()
+                    .find(|it| it.type_name == component.type_name)
+                {
+                    Some(existing) => *existing = component.clone(),
+                    None => components.push(component.clone()),
+                }
+
+                vec![
+                    answer(RuntimeToEditorMsg::ComponentInserted {
+                        entity,
+                        type_name: component.type_name.clone(),
+                    }),
+                    // like a real runtime, which notices the change on its next frame
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned: vec![],
+                            despawned: vec![],
+                            components_changed: vec![(entity, vec![component])],
+                            components_removed: vec![],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            EditorToRuntimeMsg::Play => {
+                if self.play_snapshot.is_none() {
+                    self.play_snapshot = Some(self.scene.clone());
+                }
+                self.paused = false;
+                vec![answer(RuntimeToEditorMsg::PlayStateChanged {
+                    state: PlayState::Playing,
+                })]
+            }
+            EditorToRuntimeMsg::Pause => {
+                if self.play_snapshot.is_none() {
+                    return error(
+                        ErrorKind::InvalidState,
+                        "can't pause: there is no game playing".to_string(),
+                    );
+                }
+                self.paused = true;
+                vec![answer(RuntimeToEditorMsg::PlayStateChanged {
+                    state: PlayState::Paused,
+                })]
+            }
+            EditorToRuntimeMsg::Stop => {
+                let Some(snapshot) = self.play_snapshot.take() else {
+                    return error(
+                        ErrorKind::InvalidState,
+                        "can't stop: there is no game playing".to_string(),
+                    );
+                };
+                self.paused = false;
+                let played = std::mem::replace(&mut self.scene, snapshot);
+                vec![
+                    answer(RuntimeToEditorMsg::PlayStateChanged {
+                        state: PlayState::Editing,
+                    }),
+                    Response {
+                        request_id: None,
+                        msg: self.scene.changes_since(&played),
+                    },
+                ]
+            }
+            EditorToRuntimeMsg::Save { path } => match self.save(&path) {
+                Ok(bytes) => {
+                    log::info!("saved the scene to {} ({} bytes)", path, bytes);
+                    vec![answer(RuntimeToEditorMsg::Saved { path, bytes })]
+                }
+                Err(error) => {
+                    log::error!("error saving the scene to {}: {}", path, error);
+                    vec![answer(RuntimeToEditorMsg::SaveFailed { path, error })]
+                }
+            },
+            EditorToRuntimeMsg::LoadScene { path, mode } => {
+                let scene = match resolve_asset_path(&self.asset_root, &path)
+                    .and_then(|file_path| MockScene::load(file_path).map_err(|err| err.to_string()))
+                {
+                    Ok(scene) => scene,
+                    Err(error) => {
+                        log::error!("error loading scene {}: {}", path, error);
+                        return vec![answer(RuntimeToEditorMsg::SceneLoadFailed { path, error })];
+                    }
+                };
+
+                // entities in the file get fresh ids, like when bevy spawns a scene
+                let mut next_index = self.next_index();
+                // the mock has no entities of its own, so replacing drops all of them
+                let despawned = match mode {
+                    LoadMode::Replace => std::mem::take(&mut self.scene.entities)
+                        .into_keys()
+                        .collect(),
+                    LoadMode::Additive => vec![],
+                };
+                let spawned = scene
+                    .entities
+                    .into_values()
+                    .map(|components| {
+                        let entity = Entity::from_raw(next_index);
+                        next_index += 1;
+                        self.scene.entities.insert(entity, components.clone());
+                        (entity, components)
+                    })
+                    .collect::<Vec<_>>();
+
+                vec![
+                    answer(RuntimeToEditorMsg::SceneLoaded {
+                        path,
+                        mode,
+                        entities: spawned.len(),
+                    }),
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned,
+                            despawned,
+                            components_changed: vec![],
+                            components_removed: vec![],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            EditorToRuntimeMsg::GetTypeRegistry => {
+                vec![answer(RuntimeToEditorMsg::TypeRegistry {
+                    schema: TypeRegistrySchema::default(),
+                })]
+            }
+            EditorToRuntimeMsg::GetResources => vec![answer(RuntimeToEditorMsg::Resources {
+                resources: self.scene.resources.clone(),
+            })],
+            EditorToRuntimeMsg::SetResource { resource } => {
+                match self
+                    .scene
+                    .resources
+                    .iter_mut()
+                    .find(|it| it.type_name == resource.type_name)
+                {
+                    Some(existing) => *existing = resource.clone(),
+                    None => self.scene.resources.push(resource.clone()),
+                }
+                vec![answer(RuntimeToEditorMsg::ResourceSet { resource })]
+            }
+            EditorToRuntimeMsg::SpawnEntity { components } => {
+                let entity = Entity::from_raw(self.next_index());
+                self.scene.entities.insert(entity, components.clone());
+                vec![
+                    answer(RuntimeToEditorMsg::EntitySpawned { entity }),
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned: vec![(entity, components)],
+                            despawned: vec![],
+                            components_changed: vec![],
+                            components_removed: vec![],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            // the mock doesn't keep track of hierarchies, there is nothing to change
+            EditorToRuntimeMsg::SetParent { child, parent } => {
+                if let Some(missing) = std::iter::once(child)
+                    .chain(parent)
+                    .find(|entity| !self.scene.entities.contains_key(entity))
+                {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!(
+                            "can't set the parent of {:?}, {:?} doesn't exist",
+                            child, missing
+                        ),
+                    );
+                }
+                vec![answer(RuntimeToEditorMsg::ParentSet { child, parent })]
+            }
+            // the mock doesn't keep track of hierarchies, so `recursive` makes no difference
+            EditorToRuntimeMsg::DuplicateEntity { entity, .. } => {
+                let Some(components) = self.scene.entities.get(&entity).cloned() else {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!("can't duplicate {:?}, it doesn't exist", entity),
+                    );
+                };
+                let copy = Entity::from_raw(self.next_index());
+                self.scene.entities.insert(copy, components.clone());
+                vec![
+                    answer(RuntimeToEditorMsg::EntityDuplicated {
+                        entity: copy,
+                        spawned: vec![copy],
+                    }),
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned: vec![(copy, components)],
+                            despawned: vec![],
+                            components_changed: vec![],
+                            components_removed: vec![],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            // the mock doesn't keep track of hierarchies, so `recursive` makes no difference
+            EditorToRuntimeMsg::DespawnEntity { entity, .. } => {
+                if self.scene.entities.remove(&entity).is_none() {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!("can't despawn {:?}, it doesn't exist", entity),
+                    );
+                }
+                vec![
+                    answer(RuntimeToEditorMsg::EntityDespawned { entity }),
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned: vec![],
+                            despawned: vec![entity],
+                            components_changed: vec![],
+                            components_removed: vec![],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            EditorToRuntimeMsg::RemoveComponent { entity, type_name } => {
+                let Some(components) = self.scene.entities.get_mut(&entity) else {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!(
+                            "can't remove {} from {:?}, it doesn't exist",
+                            type_name, entity
+                        ),
+                    );
+                };
+                components.retain(|it| it.type_name != type_name);
+                vec![
+                    answer(RuntimeToEditorMsg::ComponentRemoved {
+                        entity,
+                        type_name: type_name.clone(),
+                    }),
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned: vec![],
+                            despawned: vec![],
+                            components_changed: vec![],
+                            components_removed: vec![(entity, vec![type_name])],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            EditorToRuntimeMsg::SetField {
+                entity,
+                component,
+                path,
+                value,
+            } => {
+                let Some(data) = self.scene.entities.get_mut(&entity).and_then(|components| {
+                    components.iter_mut().find(|it| it.type_name == component)
+                }) else {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!(
+                            "can't set {} of {}, {:?} has no such component",
+                            path, component, entity
+                        ),
+                    );
+                };
+                // unlike bevy, the mock can't check that `value` is of the field's type
+                let Some(field) = data.value.as_mut().and_then(|it| it.path_mut(&path)) else {
+                    return error(
+                        ErrorKind::InvalidPath,
+                        format!(
+                            "can't set {} of {}, there is no such field",
+                            path, component
+                        ),
+                    );
+                };
+                *field = value;
+
+                vec![
+                    answer(RuntimeToEditorMsg::FieldSet {
+                        entity,
+                        component,
+                        path,
+                    }),
+                    Response {
+                        request_id: None,
+                        msg: RuntimeToEditorMsg::EntitiesChanged {
+                            spawned: vec![],
+                            despawned: vec![],
+                            components_changed: vec![(entity, vec![data.clone()])],
+                            components_removed: vec![],
+                            hierarchy_changed: vec![],
+                        },
+                    },
+                ]
+            }
+            EditorToRuntimeMsg::SetResourceField {
+                resource,
+                path,
+                value,
+            } => {
+                let Some(data) = self
+                    .scene
+                    .resources
+                    .iter_mut()
+                    .find(|it| it.type_name == resource)
+                else {
+                    return error(
+                        ErrorKind::NotFound,
+                        format!("can't set {} of {}, it doesn't exist", path, resource),
+                    );
+                };
+                let Some(field) = data.value.as_mut().and_then(|it| it.path_mut(&path)) else {
+                    return error(
+                        ErrorKind::InvalidPath,
+                        format!("can't set {} of {}, there is no such field", path, resource),
+                    );
+                };
+                *field = value;
+
+                let resource = self
+                    .scene
+                    .resources
+                    .iter()
+                    .find(|it| it.type_name == resource)
+                    .unwrap()
+                    .clone();
+                vec![answer(RuntimeToEditorMsg::ResourceSet { resource })]
+            }
+            // there is no window to forward input or layout to
+            _ => vec![],
+        }
+    }
+
+    /// Does the runtime's half of the handshake, then answers requests until the editor sends
+    /// `Shutdown` or goes away.
+    pub fn serve(mut self, transport: Arc<dyn Transport>) -> Result<(), MockError> {
+        let hello = Self::hello();
+        transport::send_message(&*transport, &hello)?;
+        match transport::recv_message::<HandshakeReply>(&*transport)? {
+            Ok(editor_hello) => hello
+                .check_compatible(&editor_hello)
+                .map_err(MockError::Handshake)?,
+            Err(err) => return Err(MockError::Handshake(HandshakeError::Refused(Box::new(err)))),
+        }
+
+        let connection = Connection::<Response, Request>::new(transport);
+        loop {
+            let request = match connection.recv() {
+                Ok(request) => request,
+                Err(TransportError::Disconnected) => return Ok(()),
+                Err(err) => return Err(err.into()),
+            };
+            let shutdown = matches!(request.msg, EditorToRuntimeMsg::Shutdown);
+
+            for response in self.handle(request) {
+                connection.send(&response)?;
+            }
+            if shutdown {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use mock_runtime::{MockRuntime, MockScene};
+use roth_shared::{launch::LaunchArgs, transport};
+
+/// Value of `--name <value>` on the command line.
+fn cli_arg(name: &str) -> Option<String> {
+    let mut args = std::env::args();
+    args.find(|arg| arg == name)?;
+    args.next()
+}
+
+/// Takes the same [`LaunchArgs`] the editor starts a real runtime with, plus `--asset-root` that scene
+/// paths are relative to and `--scene` to serve, an asset path that defaults to the main scene.
+fn main() {
+    simple_logger::SimpleLogger::new()
+        .with_level(log::LevelFilter::Info)
+        .init()
+        .unwrap();
+
+    // there is nothing to do without an editor, unlike a real runtime it can't run standalone
+    let launch = match LaunchArgs::from_env() {
+        Ok(Some(launch)) => launch,
+        Ok(None) => {
+            log::error!("no editor to attach to, pass --roth-ipc or set ROTH_IPC");
+            std::process::exit(1);
+        }
+        Err(err) => {
+            log::error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let asset_root = cli_arg("--asset-root")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
+    let scene_path =
+        asset_root.join(cli_arg("--scene").unwrap_or_else(|| "scenes/main.scn.ron".to_string()));
+
+    let scene = match MockScene::load(&scene_path) {
+        Ok(scene) => scene,
+        Err(err) => {
+            log::error!("could not load {}: {}", scene_path.display(), err);
+            std::process::exit(1);
+        }
+    };
+    log::info!(
+        "serving {} entities from {}",
+        scene.entities.len(),
+        scene_path.display()
+    );
+
+    let transport = match transport::connect(launch.transport, &launch.ipc) {
+        Ok(transport) => transport,
+        Err(err) => {
+            log::error!("not attaching to the editor at {}: {}", launch.ipc, err);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(err) = MockRuntime::new(scene, asset_root).serve(transport) {
+        log::error!("{}", err);
+        std::process::exit(1);
+    }
+}
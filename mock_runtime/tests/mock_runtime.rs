@@ -0,0 +1,474 @@
+use std::{path::PathBuf, thread};
+
+use bevy::ecs::entity::Entity;
+use mock_runtime::{MockRuntime, MockScene};
+use roth_shared::{
+    handshake::{Capabilities, HandshakeReply, Hello},
+    transport::{self, Connection, TransportKind, TransportListener},
+    value::VariantValue,
+    ComponentData, EditorToRuntimeMsg, ErrorKind, LoadMode, PlayState, ReflectValue, Request,
+    RequestId, Response, RuntimeToEditorMsg,
+};
+
+const SCENE: &str = r#"(
+  resources: {
+    "bevy_pbr::light::AmbientLight": (brightness: 8.0),
+  },
+  entities: {
+    4294967296: (
+      components: {
+        "bevy_core::name::Name": "Cube",
+        "bevy_transform::components::transform::Transform": (
+          translation: (x: 0.0, y: 0.5, z: 0.0),
+        ),
+      },
+    ),
+    4294967297: (
+      components: {
+        "bevy_core::name::Name": "Light",
+      },
+    ),
+  },
+)"#;
+
+/// An empty directory to use as the asset folder.
+fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("roth-mock-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    path
+}
+
+/// Serves `SCENE` over tcp and returns the editor's end after the handshake.
+fn connect(asset_root: PathBuf) -> Connection<Request, Response> {
+    let (listener, address) = TransportListener::bind(TransportKind::Tcp, "127.0.0.1:0").unwrap();
+    thread::spawn(move || {
+        let transport = transport::connect(TransportKind::Tcp, &address).unwrap();
+        MockRuntime::new(MockScene::from_ron(SCENE).unwrap(), asset_root)
+            .serve(transport)
+            .unwrap();
+    });
+
+    let editor = listener.accept().unwrap();
+    let hello = transport::recv_message::<Hello>(&*editor).unwrap();
+    assert_eq!(hello, MockRuntime::hello());
+    transport::send_message::<HandshakeReply>(&*editor, &Ok(Hello::editor(Capabilities::NONE)))
+        .unwrap();
+
+    Connection::new(editor)
+}
+
+fn request(connection: &Connection<Request, Response>, id: u64, msg: EditorToRuntimeMsg) {
+    connection
+        .send(&Request {
+            id: RequestId(id),
+            msg,
+        })
+        .unwrap();
+}
+
+fn name(value: &str) -> ComponentData {
+    ComponentData {
+        type_name: "bevy_core::name::Name".to_string(),
+        value: Some(ReflectValue::String(value.to_string())),
+    }
+}
+
+#[test]
+fn parses_scene() {
+    let scene = MockScene::from_ron(SCENE).unwrap();
+    assert_eq!(scene.entities.len(), 2);
+    assert_eq!(scene.resources.len(), 1);
+
+    let cube = scene.entities.values().next().unwrap();
+    assert_eq!(cube[0], name("Cube"));
+    assert_eq!(
+        cube[1]
+            .value
+            .as_ref()
+            .and_then(|it| it.field("translation"))
+            .and_then(|it| it.field("y")),
+        Some(&ReflectValue::F64(0.5))
+    );
+
+    assert_eq!(
+        MockScene::from_ron(&scene.to_ron().unwrap()).unwrap(),
+        scene
+    );
+}
+
+#[test]
+fn answers_get_entities() {
+    let connection = connect(temp_dir("entities"));
+    request(&connection, 1, EditorToRuntimeMsg::GetEntities);
+
+    let response = connection.recv().unwrap();
+    assert_eq!(response.request_id, Some(RequestId(1)));
+    let RuntimeToEditorMsg::Entities { entities, .. } = response.msg else {
+        panic!("expected entities, got {:?}", response.msg);
+    };
+    assert_eq!(entities.len(), 2);
+    assert_eq!(entities[1].1, vec![name("Light")]);
+}
+
+#[test]
+fn insert_component_is_answered_and_diffed() {
+    let connection = connect(temp_dir("insert"));
+    let (entity, _) = MockScene::from_ron(SCENE)
+        .unwrap()
+        .entities
+        .pop_first()
+        .unwrap();
+    request(
+        &connection,
+        7,
+        EditorToRuntimeMsg::InsertComponent {
+            entity,
+            component: name("Sphere"),
+        },
+    );
+
+    let answer = connection.recv().unwrap();
+    assert_eq!(answer.request_id, Some(RequestId(7)));
+    assert!(matches!(
+        answer.msg,
+        RuntimeToEditorMsg::ComponentInserted { entity: inserted, ref type_name }
+            if inserted == entity && type_name == "bevy_core::name::Name"
+    ));
+
+    let diff = connection.recv().unwrap();
+    assert_eq!(diff.request_id, None);
+    let RuntimeToEditorMsg::EntitiesChanged {
+        components_changed, ..
+    } = diff.msg
+    else {
+        panic!("expected a diff, got {:?}", diff.msg);
+    };
+    assert_eq!(components_changed, vec![(entity, vec![name("Sphere")])]);
+}
+
+#[test]
+fn save_writes_a_loadable_scene() {
+    let asset_root = temp_dir("save");
+    let connection = connect(asset_root.clone());
+    request(
+        &connection,
+        1,
+        EditorToRuntimeMsg::Save {
+            path: "saved.scn.ron".to_string(),
+        },
+    );
+    let saved = connection.recv().unwrap();
+    assert_eq!(saved.request_id, Some(RequestId(1)));
+    assert!(
+        matches!(&saved.msg, RuntimeToEditorMsg::Saved { path, .. } if path == "saved.scn.ron"),
+        "expected the scene to be saved, got {:?}",
+        saved.msg
+    );
+
+    let saved = MockScene::load(asset_root.join("saved.scn.ron")).unwrap();
+    std::fs::remove_dir_all(&asset_root).unwrap();
+    assert_eq!(saved, MockScene::from_ron(SCENE).unwrap());
+}
+
+#[test]
+fn save_keeps_the_fields_of_enum_variants() {
+    let mut scene = MockScene::from_ron(SCENE).unwrap();
+    let (_, components) = scene.entities.first_key_value().unwrap();
+    let entity = scene.entities.keys().next().copied().unwrap();
+    let mut components = components.clone();
+    components.push(ComponentData {
+        type_name: "bevy_render::color::Color".to_string(),
+        value: Some(ReflectValue::Enum {
+            variant: "Rgba".to_string(),
+            value: VariantValue::Struct(vec![
+                ("red".to_string(), ReflectValue::F32(0.25)),
+                ("alpha".to_string(), ReflectValue::F32(1.0)),
+            ]),
+        }),
+    });
+    scene.entities.insert(entity, components);
+
+    let saved = MockScene::from_ron(&scene.to_ron().unwrap()).unwrap();
+    let color = saved.entities[&entity]
+        .iter()
+        .find(|it| it.type_name == "bevy_render::color::Color")
+        .unwrap();
+    assert_eq!(
+        color.value,
+        Some(ReflectValue::Struct(vec![(
+            "Rgba".to_string(),
+            ReflectValue::Struct(vec![
+                ("alpha".to_string(), ReflectValue::F64(1.0)),
+                ("red".to_string(), ReflectValue::F64(0.25)),
+            ]),
+        )]))
+    );
+}
+
+#[test]
+fn save_fails_on_integers_that_dont_fit_in_an_i64() {
+    let mut scene = MockScene::from_ron(SCENE).unwrap();
+    let entity = scene.entities.keys().next().copied().unwrap();
+    scene.entities.get_mut(&entity).unwrap().push(ComponentData {
+        type_name: "game::Seed".to_string(),
+        value: Some(ReflectValue::U64(u64::MAX)),
+    });
+
+    let error = scene.to_ron().unwrap_err();
+    assert!(error.contains("game::Seed"), "unexpected error: {}", error);
+}
+
+#[test]
+fn save_refuses_paths_outside_of_the_asset_root() {
+    let asset_root = temp_dir("save-outside");
+    let connection = connect(asset_root.clone());
+    request(
+        &connection,
+        1,
+        EditorToRuntimeMsg::Save {
+            path: "/tmp/saved.scn.ron".to_string(),
+        },
+    );
+    let saved = connection.recv().unwrap();
+    std::fs::remove_dir_all(&asset_root).unwrap();
+
+    assert!(
+        matches!(saved.msg, RuntimeToEditorMsg::SaveFailed { .. }),
+        "expected the save to fail, got {:?}",
+        saved.msg
+    );
+}
+
+#[test]
+fn load_scene_replaces_the_entities() {
+    let asset_root = temp_dir("load");
+    std::fs::write(asset_root.join("level.scn.ron"), SCENE).unwrap();
+    let connection = connect(asset_root.clone());
+    request(
+        &connection,
+        1,
+        EditorToRuntimeMsg::LoadScene {
+            path: "level.scn.ron".to_string(),
+            mode: LoadMode::Replace,
+        },
+    );
+
+    let answer = connection.recv().unwrap();
+    let diff = connection.recv().unwrap();
+    std::fs::remove_dir_all(&asset_root).unwrap();
+
+    assert_eq!(answer.request_id, Some(RequestId(1)));
+    assert!(
+        matches!(
+            answer.msg,
+            RuntimeToEditorMsg::SceneLoaded { entities: 2, .. }
+        ),
+        "expected the scene to be loaded, got {:?}",
+        answer.msg
+    );
+    let RuntimeToEditorMsg::EntitiesChanged {
+        spawned, despawned, ..
+    } = diff.msg
+    else {
+        panic!("expected a diff, got {:?}", diff.msg);
+    };
+    assert_eq!(spawned.len(), 2);
+    assert_eq!(despawned.len(), 2);
+}
+
+#[test]
+fn spawn_and_despawn_entity() {
+    let connection = connect(temp_dir("spawn"));
+    request(
+        &connection,
+        1,
+        EditorToRuntimeMsg::SpawnEntity {
+            components: vec![name("Sphere")],
+        },
+    );
+    let answer = connection.recv().unwrap();
+    let RuntimeToEditorMsg::EntitySpawned { entity } = answer.msg else {
+        panic!("expected the entity to be spawned, got {:?}", answer.msg);
+    };
+    // `SCENE`'s entities are 0 and 1
+    assert_eq!(entity.index(), 2);
+    connection.recv().unwrap();
+
+    request(
+        &connection,
+        2,
+        EditorToRuntimeMsg::DespawnEntity {
+            entity,
+            recursive: true,
+        },
+    );
+    let answer = connection.recv().unwrap();
+    assert!(matches!(
+        answer.msg,
+        RuntimeToEditorMsg::EntityDespawned { entity: despawned } if despawned == entity
+    ));
+    let diff = connection.recv().unwrap();
+    assert!(matches!(
+        diff.msg,
+        RuntimeToEditorMsg::EntitiesChanged { ref despawned, .. } if despawned == &vec![entity]
+    ));
+}
+
+#[test]
+fn set_field_changes_only_that_field() {
+    let connection = connect(temp_dir("set-field"));
+    let (entity, _) = MockScene::from_ron(SCENE)
+        .unwrap()
+        .entities
+        .pop_first()
+        .unwrap();
+    request(
+        &connection,
+        1,
+        EditorToRuntimeMsg::SetField {
+            entity,
+            component: "bevy_transform::components::transform::Transform".to_string(),
+            path: "translation.x".to_string(),
+            value: ReflectValue::F64(2.0),
+        },
+    );
+
+    let answer = connection.recv().unwrap();
+    assert!(matches!(
+        answer.msg,
+        RuntimeToEditorMsg::FieldSet { entity: changed, ref path, .. }
+            if changed == entity && path == "translation.x"
+    ));
+    let diff = connection.recv().unwrap();
+    let RuntimeToEditorMsg::EntitiesChanged {
+        mut components_changed,
+        ..
+    } = diff.msg
+    else {
+        panic!("expected a diff, got {:?}", diff.msg);
+    };
+    let mut transform = components_changed.remove(0).1.remove(0).value.unwrap();
+    assert_eq!(
+        transform.path_mut("translation.x"),
+        Some(&mut ReflectValue::F64(2.0))
+    );
+    assert_eq!(
+        transform.path_mut("translation.y"),
+        Some(&mut ReflectValue::F64(0.5))
+    );
+}
+
+#[test]
+fn failures_are_answered_with_errors() {
+    let connection = connect(temp_dir("errors"));
+    request(
+        &connection,
+        1,
+        EditorToRuntimeMsg::DespawnEntity {
+            entity: Entity::from_raw(100),
+            recursive: false,
+        },
+    );
+
+    let answer = connection.recv().unwrap();
+    assert_eq!(answer.request_id, Some(RequestId(1)));
+    assert!(matches!(
+        answer.msg,
+        RuntimeToEditorMsg::Error {
+            request_id: Some(RequestId(1)),
+            kind: ErrorKind::NotFound,
+            ..
+        }
+    ));
+
+    // like the real runtime, nothing is made up for what doesn't exist
+    request(
+        &connection,
+        2,
+        EditorToRuntimeMsg::InsertComponent {
+            entity: Entity::from_raw(100),
+            component: name("Ghost"),
+        },
+    );
+    request(
+        &connection,
+        3,
+        EditorToRuntimeMsg::SetResourceField {
+            resource: "bevy_pbr::light::ClearColor".to_string(),
+            path: "0".to_string(),
+            value: ReflectValue::F32(1.0),
+        },
+    );
+    for id in [2, 3] {
+        let answer = connection.recv().unwrap();
+        assert!(
+            matches!(
+                answer.msg,
+                RuntimeToEditorMsg::Error {
+                    kind: ErrorKind::NotFound,
+                    ..
+                }
+            ),
+            "expected {} to not be found, got {:?}",
+            id,
+            answer.msg
+        );
+    }
+    request(&connection, 4, EditorToRuntimeMsg::GetEntities);
+    let RuntimeToEditorMsg::Entities { entities, .. } = connection.recv().unwrap().msg else {
+        panic!("expected entities");
+    };
+    assert_eq!(entities.len(), 2);
+}
+
+#[test]
+fn stop_restores_the_scene_from_before_play() {
+    let connection = connect(temp_dir("play"));
+    let (entity, components) = MockScene::from_ron(SCENE)
+        .unwrap()
+        .entities
+        .pop_first()
+        .unwrap();
+    request(&connection, 1, EditorToRuntimeMsg::Play);
+    assert!(matches!(
+        connection.recv().unwrap().msg,
+        RuntimeToEditorMsg::PlayStateChanged {
+            state: PlayState::Playing
+        }
+    ));
+
+    request(
+        &connection,
+        2,
+        EditorToRuntimeMsg::DespawnEntity {
+            entity,
+            recursive: true,
+        },
+    );
+    connection.recv().unwrap();
+    connection.recv().unwrap();
+
+    request(&connection, 3, EditorToRuntimeMsg::Stop);
+    let answer = connection.recv().unwrap();
+    assert_eq!(answer.request_id, Some(RequestId(3)));
+    assert!(matches!(
+        answer.msg,
+        RuntimeToEditorMsg::PlayStateChanged {
+            state: PlayState::Editing
+        }
+    ));
+    let RuntimeToEditorMsg::EntitiesChanged { spawned, .. } = connection.recv().unwrap().msg else {
+        panic!("expected the despawned entity to come back");
+    };
+    assert_eq!(spawned, vec![(entity, components)]);
+
+    request(&connection, 4, EditorToRuntimeMsg::Stop);
+    assert!(matches!(
+        connection.recv().unwrap().msg,
+        RuntimeToEditorMsg::Error {
+            kind: ErrorKind::InvalidState,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,171 @@
+use std::{fmt, ops::BitOr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::transport::TransportError;
+
+/// Bump this whenever [`crate::EditorToRuntimeMsg`] or [`crate::RuntimeToEditorMsg`] change shape,
+/// the editor and runtime refuse to talk to each other when it differs.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Version of the bevy dependency this crate is built against, keep in sync with `Cargo.toml`.
+pub const BEVY_VERSION: &str = "0.12.0";
+
+/// Features one side of the connection supports, exchanged in the [`Hello`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    pub const NONE: Self = Self(0);
+    /// Forwarding of cursor, mouse and keyboard input into the runtime window
+    pub const INPUT: Self = Self(1 << 0);
+    /// `GetEntities` and `InsertComponent`
+    pub const ENTITIES: Self = Self(1 << 1);
+    /// `Save` and `LoadScene`
+    pub const SCENES: Self = Self(1 << 2);
+    /// `EntitiesChanged` deltas after the first `GetEntities`
+    pub const ENTITY_DIFFS: Self = Self(1 << 3);
+    /// `GetTypeRegistry`
+    pub const TYPE_REGISTRY: Self = Self(1 << 4);
+    /// `GetResources` and `SetResource`
+    pub const RESOURCES: Self = Self(1 << 5);
+    /// `Log` records forwarded from the runtime's logger
+    pub const LOGS: Self = Self(1 << 6);
+    /// `Diagnostics` sent periodically
+    pub const DIAGNOSTICS: Self = Self(1 << 7);
+    /// `Heartbeat` every [`crate::HEARTBEAT_INTERVAL`]
+    pub const HEARTBEAT: Self = Self(1 << 8);
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Describes the game that is running the editor plugin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeInfo {
+    pub name: String,
+    pub version: String,
+}
+
+/// The first thing both sides send to each other, before any other message is exchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hello {
+    pub protocol_version: u32,
+    /// version of `roth_shared` the sender was built with
+    pub roth_version: String,
+    pub bevy_version: String,
+    pub capabilities: Capabilities,
+    /// only set by the runtime
+    pub runtime: Option<RuntimeInfo>,
+}
+
+impl Hello {
+    pub fn editor(capabilities: Capabilities) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            roth_version: env!("CARGO_PKG_VERSION").to_string(),
+            bevy_version: BEVY_VERSION.to_string(),
+            capabilities,
+            runtime: None,
+        }
+    }
+
+    pub fn runtime(capabilities: Capabilities, runtime: RuntimeInfo) -> Self {
+        Self {
+            runtime: Some(runtime),
+            ..Self::editor(capabilities)
+        }
+    }
+
+    /// Checks whether we can talk to the side that sent `remote`.
+    pub fn check_compatible(&self, remote: &Hello) -> Result<(), HandshakeError> {
+        if self.protocol_version != remote.protocol_version {
+            return Err(HandshakeError::ProtocolMismatch {
+                local: self.protocol_version,
+                remote: remote.protocol_version,
+                remote_roth_version: remote.roth_version.clone(),
+            });
+        }
+
+        if self.bevy_version != remote.bevy_version {
+            return Err(HandshakeError::BevyMismatch {
+                local: self.bevy_version.clone(),
+                remote: remote.bevy_version.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// What the editor answers to the [`Hello`] the runtime sends right after connecting.
+pub type HandshakeReply = Result<Hello, HandshakeError>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HandshakeError {
+    ProtocolMismatch {
+        local: u32,
+        remote: u32,
+        remote_roth_version: String,
+    },
+    BevyMismatch {
+        local: String,
+        remote: String,
+    },
+    /// The other side sent something we couldn't decode, most likely a build of `roth_shared`
+    /// from before the handshake existed.
+    Malformed(String),
+    /// The connection couldn't be set up or broke before the handshake finished.
+    Transport(String),
+    /// The other side refused the connection after looking at our [`Hello`].
+    Refused(Box<HandshakeError>),
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandshakeError::ProtocolMismatch {
+                local,
+                remote,
+                remote_roth_version,
+            } => write!(
+                f,
+                "protocol version mismatch: we speak v{local}, the other side speaks v{remote} (roth_shared {remote_roth_version}). Rebuild the game against the same roth_shared as the editor."
+            ),
+            HandshakeError::BevyMismatch { local, remote } => write!(
+                f,
+                "bevy version mismatch: we are built against bevy {local}, the other side against bevy {remote}"
+            ),
+            HandshakeError::Malformed(err) => write!(
+                f,
+                "could not decode the handshake, the other side was probably built against an incompatible roth_shared: {err}"
+            ),
+            HandshakeError::Transport(err) => write!(f, "could not connect: {err}"),
+            HandshakeError::Refused(reason) => write!(f, "connection refused: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
+impl From<TransportError> for HandshakeError {
+    fn from(err: TransportError) -> Self {
+        match err {
+            TransportError::Malformed(err) => HandshakeError::Malformed(err),
+            err => HandshakeError::Transport(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use crate::transport::TransportKind;
+
+/// Address of the editor's [`crate::transport::TransportListener`], falls back to [`IPC_VAR`].
+pub const IPC_FLAG: &str = "--roth-ipc";
+/// [`TransportKind`] to connect with, falls back to [`TRANSPORT_VAR`].
+pub const TRANSPORT_FLAG: &str = "--roth-transport";
+/// Id of the editor window the runtime is shown in, falls back to [`WINDOW_ID_VAR`].
+pub const WINDOW_ID_FLAG: &str = "--roth-window-id";
+/// The editor window's id as bevy's winit backend reads it to show the runtime in that window,
+/// passed along with [`WINDOW_ID_FLAG`] and first, where it always was.
+pub const WINIT_WINDOW_ID_FLAG: &str = "--window-id";
+/// Where the runtime records the session to, falls back to [`RECORD_VAR`].
+pub const RECORD_FLAG: &str = "--roth-record";
+
+pub const IPC_VAR: &str = "ROTH_IPC";
+pub const TRANSPORT_VAR: &str = "ROTH_TRANSPORT";
+pub const WINDOW_ID_VAR: &str = "ROTH_WINDOW_ID";
+/// not `ROTH_RECORD`, the editor records its own side with that and the runtime inherits it
+pub const RECORD_VAR: &str = "ROTH_RUNTIME_RECORD";
+
+/// How the editor started the runtime and where to reach it. A runtime started without any of
+/// these isn't attached to an editor and runs standalone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchArgs {
+    pub transport: TransportKind,
+    pub ipc: String,
+    pub window_id: Option<u64>,
+    pub record: Option<PathBuf>,
+}
+
+impl LaunchArgs {
+    pub fn new(transport: TransportKind, ipc: impl Into<String>) -> Self {
+        Self {
+            transport,
+            ipc: ipc.into(),
+            window_id: None,
+            record: None,
+        }
+    }
+
+    /// Reads the process' command line and environment, `None` when the runtime runs standalone.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let args = std::env::args().collect::<Vec<_>>();
+        Self::parse(&args, |name| std::env::var(name).ok())
+    }
+
+    /// Flags are either `--roth-ipc <value>` or `--roth-ipc=<value>` and win over the environment
+    /// variable looked up through `var`. Unrelated arguments are ignored, so they can be mixed with
+    /// the game's own.
+    pub fn parse(
+        args: &[String],
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Option<Self>, String> {
+        let value = |flag: &str, name: &str| flag_value(args, flag).or_else(|| var(name));
+
+        let ipc = value(IPC_FLAG, IPC_VAR);
+        let transport = value(TRANSPORT_FLAG, TRANSPORT_VAR);
+        let window_id = value(WINDOW_ID_FLAG, WINDOW_ID_VAR);
+        let record = value(RECORD_FLAG, RECORD_VAR);
+
+        let Some(ipc) = ipc else {
+            if transport.is_some() || window_id.is_some() || record.is_some() {
+                return Err(format!("expected {} or {}", IPC_FLAG, IPC_VAR));
+            }
+            return Ok(None);
+        };
+        let transport = match transport {
+            Some(transport) => transport.parse()?,
+            None => TransportKind::default(),
+        };
+        let window_id = window_id
+            .map(|it| {
+                it.parse::<u64>()
+                    .map_err(|_| format!("{} expects a number, got `{}`", WINDOW_ID_FLAG, it))
+            })
+            .transpose()?;
+
+        Ok(Some(Self {
+            transport,
+            ipc,
+            window_id,
+            record: record.map(PathBuf::from),
+        }))
+    }
+
+    /// The flags to start a runtime with, the inverse of [`LaunchArgs::parse`].
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(window_id) = self.window_id {
+            args.extend([WINIT_WINDOW_ID_FLAG.to_string(), window_id.to_string()]);
+        }
+        args.extend([
+            TRANSPORT_FLAG.to_string(),
+            self.transport.to_string(),
+            IPC_FLAG.to_string(),
+            self.ipc.clone(),
+        ]);
+        if let Some(window_id) = self.window_id {
+            args.extend([WINDOW_ID_FLAG.to_string(), window_id.to_string()]);
+        }
+        if let Some(record) = &self.record {
+            args.extend([RECORD_FLAG.to_string(), record.display().to_string()]);
+        }
+        args
+    }
+}
+
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().cloned();
+        }
+        if let Some(value) = arg
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
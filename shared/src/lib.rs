@@ -1,15 +1,67 @@
-use ::ron::Value;
+use std::{
+    path::{Component, Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
 use bevy::ecs::entity::Entity;
 use serde::{Deserialize, Serialize};
 use winit::{dpi::PhysicalPosition, keyboard::SmolStr};
 
-#[derive(Debug, Serialize, Deserialize)]
+pub mod handshake;
+pub mod launch;
+pub mod recording;
+pub mod schema;
+pub mod transport;
+pub mod value;
+
+pub use schema::TypeRegistrySchema;
+pub use value::{ReflectValue, ValueError};
+
+/// How often a runtime with [`handshake::Capabilities::HEARTBEAT`] sends a `Heartbeat`.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Identifies a [`Request`], the runtime copies it into every [`Response`] it sends as an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RequestId(pub u64);
+
+/// Envelope around every message the editor sends to the runtime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub id: RequestId,
+    pub msg: EditorToRuntimeMsg,
+}
+
+/// Envelope around every message the runtime sends to the editor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    /// set when this is the answer to a [`Request`], `None` for messages the runtime sends on its own
+    pub request_id: Option<RequestId>,
+    pub msg: RuntimeToEditorMsg,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EditorToRuntimeMsg {
     Shutdown,
-    Save,
+    /// Writes the world as a scene, answered with `Saved` or `SaveFailed`.
+    Save {
+        /// relative to the runtime's asset root, see [`resolve_asset_path`]
+        path: String,
+    },
+    /// Loads a scene through the `AssetServer` and spawns it, answered with `SceneLoaded` or
+    /// `SceneLoadFailed` once it's done.
     LoadScene {
+        /// asset path of the scene
         path: String,
+        mode: LoadMode,
     },
+    /// Snapshots the world and switches the runtime to `EditorState::Game`, or resumes the game
+    /// when it's paused. Answered with `PlayStateChanged`.
+    Play,
+    /// Pauses the game's virtual time, answered with `PlayStateChanged`.
+    Pause,
+    /// Puts the world back the way it was before `Play` and switches to `EditorState::Editor`,
+    /// answered with `PlayStateChanged`.
+    Stop,
     LayoutChange {
         min: (f32, f32),
         width: f32,
@@ -37,98 +89,311 @@ pub enum EditorToRuntimeMsg {
         char: SmolStr,
     },
     GetEntities,
+    /// Inserts the component or updates it when the entity has it already, answered with
+    /// `ComponentInserted`.
     InsertComponent {
         entity: Entity,
-        component: RonComponentSerialized,
+        component: ComponentData,
+    },
+    /// Spawns an entity with `components`, answered with `EntitySpawned`.
+    SpawnEntity {
+        components: Vec<ComponentData>,
+    },
+    /// Answered with `EntityDespawned`.
+    DespawnEntity {
+        entity: Entity,
+        /// whether its children go with it, otherwise they're left without a parent
+        recursive: bool,
+    },
+    /// Spawns a copy of the entity with a clone of each of its reflected components, next to it
+    /// under the same parent. Answered with `EntityDuplicated`.
+    DuplicateEntity {
+        entity: Entity,
+        /// whether its descendants are copied along with it
+        recursive: bool,
+    },
+    /// Answered with `ComponentRemoved`.
+    RemoveComponent {
+        entity: Entity,
+        type_name: String,
+    },
+    /// Moves `child` under `parent`, or to the root when it's `None`. Its `Transform` is changed so it
+    /// keeps its `GlobalTransform`. Answered with `ParentSet`.
+    SetParent {
+        child: Entity,
+        parent: Option<Entity>,
+    },
+    /// Overwrites a single field of a component, leaving the rest of it as the runtime has it.
+    /// Answered with `FieldSet`.
+    SetField {
+        entity: Entity,
+        /// type name of the component
+        component: String,
+        /// reflect path to the field, e.g. `translation.x`, see [`ReflectValue::path_mut`]
+        path: String,
+        value: ReflectValue,
+    },
+    GetTypeRegistry,
+    GetResources,
+    /// Overwrites the resource, or inserts it when it doesn't exist yet.
+    SetResource {
+        resource: ComponentData,
+    },
+    /// Like `SetField` for a resource that exists already, answered with `ResourceSet`.
+    SetResourceField {
+        /// type name of the resource
+        resource: String,
+        path: String,
+        value: ReflectValue,
     },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RuntimeToEditorMsg {
+    /// Full snapshot of the world, the answer to `GetEntities`.
     Entities {
-        entities: Vec<(Entity, Vec<RonComponentSerialized>)>,
+        entities: Vec<(Entity, Vec<ComponentData>)>,
+        /// every entity that has children, with its children in order
+        hierarchy: Vec<(Entity, Vec<Entity>)>,
     },
+    /// Everything that changed since the last `Entities` or `EntitiesChanged`, sent unprompted.
+    EntitiesChanged {
+        spawned: Vec<(Entity, Vec<ComponentData>)>,
+        despawned: Vec<Entity>,
+        /// only the components that were added or changed, not the whole entity
+        components_changed: Vec<(Entity, Vec<ComponentData>)>,
+        /// type names of the components that were removed
+        components_removed: Vec<(Entity, Vec<String>)>,
+        /// entities whose children changed with all of their children now, empty when they have
+        /// none left
+        hierarchy_changed: Vec<(Entity, Vec<Entity>)>,
+    },
+    ComponentInserted {
+        entity: Entity,
+        type_name: String,
+    },
+    EntitySpawned {
+        entity: Entity,
+    },
+    EntityDespawned {
+        entity: Entity,
+    },
+    EntityDuplicated {
+        /// the copy of the entity that was duplicated
+        entity: Entity,
+        /// every entity that was spawned, `entity` first, then the copies of its descendants
+        spawned: Vec<Entity>,
+    },
+    ComponentRemoved {
+        entity: Entity,
+        type_name: String,
+    },
+    ParentSet {
+        child: Entity,
+        parent: Option<Entity>,
+    },
+    /// The answer to `SetField`, the new value arrives with the next `EntitiesChanged`.
+    FieldSet {
+        entity: Entity,
+        component: String,
+        path: String,
+    },
+    /// The answer to `Play`, `Pause` and `Stop`.
+    PlayStateChanged {
+        state: PlayState,
+    },
+    /// The scene was written, the answer to `Save`.
+    Saved {
+        /// the path it was asked to save to
+        path: String,
+        /// size of the written file
+        bytes: usize,
+    },
+    /// The scene couldn't be serialized or written, the answer to `Save`.
+    SaveFailed {
+        path: String,
+        error: String,
+    },
+    /// A scene from `LoadScene` moved on to `stage`, sent unprompted while it loads.
+    SceneLoading {
+        path: String,
+        stage: SceneLoadStage,
+    },
+    /// The scene was spawned, the answer to `LoadScene`.
+    SceneLoaded {
+        path: String,
+        mode: LoadMode,
+        /// how many entities the scene spawned
+        entities: usize,
+    },
+    /// The scene couldn't be loaded or spawned, the answer to `LoadScene`.
+    SceneLoadFailed {
+        path: String,
+        error: String,
+    },
+    /// Every type in the runtime's `AppTypeRegistry`, the answer to `GetTypeRegistry`.
+    TypeRegistry {
+        schema: TypeRegistrySchema,
+    },
+    /// Every reflected resource in the world, the answer to `GetResources`.
+    Resources {
+        resources: Vec<ComponentData>,
+    },
+    /// The resource as it is after a `SetResource` or `SetResourceField`.
+    ResourceSet {
+        resource: ComponentData,
+    },
+    /// A record the runtime logged, sent unprompted.
+    Log {
+        level: LogLevel,
+        target: String,
+        message: String,
+        /// every other field of the record, formatted with `Debug`
+        fields: Vec<(String, String)>,
+        timestamp: SystemTime,
+    },
+    /// Current value of every diagnostic in the runtime's `DiagnosticsStore`, sent unprompted in a
+    /// regular interval.
+    Diagnostics {
+        diagnostics: Vec<DiagnosticValue>,
+    },
+    /// A request failed, sent as its answer in place of the usual one. `Save` and `LoadScene` have
+    /// their own answers for failures and don't send this.
+    Error {
+        /// the request that failed, the same as the [`Response`]'s, `None` for failures that don't
+        /// belong to a request
+        request_id: Option<RequestId>,
+        kind: ErrorKind,
+        message: String,
+    },
+    /// Sent unprompted every [`HEARTBEAT_INTERVAL`] at the end of a frame, so the editor notices
+    /// when the runtime hangs.
+    Heartbeat,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct RonComponentSerialized {
-    /// we store this, so we can show the name of the types that aren't serializable
-    pub type_name: String,
-    /// actual RON value, will again contain the type name as a key
-    pub value: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct RonComponent {
-    /// we store this, so we can show the name of the types that aren't serializable
-    pub type_name: String,
-    /// actual RON value, will again contain the type name as a key
-    pub value: Value,
+/// Why a request failed, see `RuntimeToEditorMsg::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorKind {
+    /// the entity, component or resource doesn't exist
+    NotFound,
+    /// the type isn't in the runtime's type registry, or not with the reflect data the request
+    /// needs, e.g. `#[reflect(Component)]`
+    NotRegistered,
+    /// the value doesn't fit the type it's for
+    InvalidValue,
+    /// there is no field at the path
+    InvalidPath,
+    /// the request doesn't apply to what the runtime is doing, e.g. `Pause` while it's not playing
+    InvalidState,
 }
 
-impl From<RonComponentSerialized> for RonComponent {
-    fn from(serialized: RonComponentSerialized) -> Self {
-        RonComponent {
-            type_name: serialized.type_name,
-            value: ron::from_str(&serialized.value).unwrap(),
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not found",
+            ErrorKind::NotRegistered => "not registered",
+            ErrorKind::InvalidValue => "invalid value",
+            ErrorKind::InvalidPath => "invalid path",
+            ErrorKind::InvalidState => "invalid state",
         }
     }
 }
 
-impl From<&RonComponentSerialized> for RonComponent {
-    fn from(component: &RonComponentSerialized) -> Self {
-        RonComponent {
-            type_name: component.type_name.clone(),
-            value: ron::from_str(&component.value).unwrap(),
-        }
-    }
+/// Whether the runtime is being edited or plays the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlayState {
+    #[default]
+    Editing,
+    Playing,
+    /// playing, with the game's virtual time paused
+    Paused,
+}
+
+/// What happens to the entities that are already there when a scene is loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoadMode {
+    /// despawns everything but the editor's own entities once the scene is ready to spawn
+    #[default]
+    Replace,
+    /// spawns the scene next to what's already there
+    Additive,
 }
 
-impl RonComponent {
-    pub fn name(&self) -> String {
-        match &self.value {
-            Value::Map(map) => {
-                let mut name = String::new();
-                map.keys().for_each(|it| {
-                    if let Value::String(s) = it {
-                        name = s.clone();
-                    }
-                });
-                name
-            }
-            _ => self.type_name.clone(),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SceneLoadStage {
+    /// the scene file itself is loading
+    Loading,
+    /// the scene is loaded, the assets it uses are still loading
+    LoadingDependencies,
+}
+
+impl SceneLoadStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SceneLoadStage::Loading => "loading",
+            SceneLoadStage::LoadingDependencies => "loading dependencies",
         }
     }
+}
 
-    pub fn short_name(&self) -> String {
-        bevy::utils::get_short_name(&self.name())
-    }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticValue {
+    /// e.g. `fps`, `frame_time` or `assets/Mesh`
+    pub path: String,
+    /// smoothed over the last few measurements
+    pub value: f64,
+    /// unit to show after the value, e.g. `ms`
+    pub suffix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
 
-    /// will return the map that contains the values. For a ``Name`` component, it will return:
-    /// ```ron
-    /// Map({String("hash"): Number(Float(Float(1.2169318712647776e19))), String("name"): String("Cube!")})
-    /// ```
-    pub fn components(&self) -> &Value {
-        let Value::Map(map) = &self.value else {
-            panic!("expected component value to always be a map");
-        };
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+}
 
-        map.values().next().unwrap()
+/// Joins the asset path `path` onto `asset_root`, refusing paths that would end up outside of it.
+pub fn resolve_asset_path(asset_root: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    let inside_root = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if path.is_empty() || !inside_root {
+        return Err(format!("`{}` is not a path inside the asset folder", path));
     }
+    Ok(asset_root.join(relative))
+}
 
-    pub fn components_mut(&mut self) -> &mut Value {
-        let Value::Map(map) = &mut self.value else {
-            panic!("expected component value to always be a map");
-        };
+/// A component or resource as the editor sees it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ComponentData {
+    /// we store this, so we can show the name of the types that aren't serializable
+    pub type_name: String,
+    /// `None` when the component isn't reflected or couldn't be serialized
+    pub value: Option<ReflectValue>,
+}
 
-        map.values_mut().next().unwrap()
+impl ComponentData {
+    pub fn short_name(&self) -> String {
+        bevy::utils::get_short_name(&self.type_name)
     }
 }
 
 pub mod ron {
     pub use ron::*;
 }
-
-pub mod serde_json {
-    pub use serde_json::*;
-}
@@ -0,0 +1,292 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    handshake::{HandshakeReply, Hello, PROTOCOL_VERSION},
+    transport::{Transport, TransportError},
+    Request, Response,
+};
+
+/// Which way a frame went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    EditorToRuntime,
+    RuntimeToEditor,
+}
+
+/// Which end of the connection a [`RecordingTransport`] or [`ReplayTransport`] stands in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Editor,
+    Runtime,
+}
+
+impl Side {
+    pub fn outgoing(self) -> Direction {
+        match self {
+            Side::Editor => Direction::EditorToRuntime,
+            Side::Runtime => Direction::RuntimeToEditor,
+        }
+    }
+
+    pub fn incoming(self) -> Direction {
+        match self {
+            Side::Editor => Direction::RuntimeToEditor,
+            Side::Runtime => Direction::EditorToRuntime,
+        }
+    }
+}
+
+/// Written once at the start of a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordingHeader {
+    protocol_version: u32,
+    roth_version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// time since the recording started
+    pub elapsed: Duration,
+    pub direction: Direction,
+    pub frame: Vec<u8>,
+}
+
+/// A decoded [`RecordedFrame`], the first frame in each direction is part of the handshake.
+#[derive(Debug, Clone)]
+pub enum RecordedMessage {
+    Hello(Hello),
+    HandshakeReply(HandshakeReply),
+    Request(Request),
+    Response(Response),
+}
+
+fn malformed(err: bincode::Error) -> TransportError {
+    TransportError::Malformed(err.to_string())
+}
+
+/// Appends every frame it is given to a file, share it between the [`RecordingTransport`]s of a session.
+pub struct Recorder {
+    writer: Mutex<BufWriter<File>>,
+    started: Instant,
+}
+
+impl Recorder {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, TransportError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = RecordingHeader {
+            protocol_version: PROTOCOL_VERSION,
+            roth_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        bincode::serialize_into(&mut writer, &header).map_err(malformed)?;
+        writer.flush()?;
+
+        Ok(Self {
+            writer: Mutex::new(writer),
+            started: Instant::now(),
+        })
+    }
+
+    pub fn record(&self, direction: Direction, frame: &[u8]) -> Result<(), TransportError> {
+        let frame = RecordedFrame {
+            elapsed: self.started.elapsed(),
+            direction,
+            frame: frame.to_vec(),
+        };
+        let mut writer = self.writer.lock().unwrap();
+        bincode::serialize_into(&mut *writer, &frame).map_err(malformed)?;
+        // flushed right away, so the recording survives the process crashing
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Passes frames through to `inner` and records them on the way.
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    recorder: Arc<Recorder>,
+    side: Side,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn Transport>, recorder: Arc<Recorder>, side: Side) -> Self {
+        Self {
+            inner,
+            recorder,
+            side,
+        }
+    }
+
+    fn record(&self, direction: Direction, frame: &[u8]) {
+        if let Err(err) = self.recorder.record(direction, frame) {
+            log::error!("failed to record frame: {}", err);
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, frame: &[u8]) -> Result<(), TransportError> {
+        self.record(self.side.outgoing(), frame);
+        self.inner.send(frame)
+    }
+
+    fn recv(&self) -> Result<Vec<u8>, TransportError> {
+        let frame = self.inner.recv()?;
+        self.record(self.side.incoming(), &frame);
+        Ok(frame)
+    }
+
+    fn try_recv(&self) -> Result<Option<Vec<u8>>, TransportError> {
+        let frame = self.inner.try_recv()?;
+        if let Some(frame) = &frame {
+            self.record(self.side.incoming(), frame);
+        }
+        Ok(frame)
+    }
+}
+
+/// A session written by a [`Recorder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TransportError> {
+        let file = File::open(path)?;
+        // same encoding as `bincode::serialize_into`, but nothing can claim to be longer than the
+        // file, so garbage doesn't make it allocate gigabytes
+        let options = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(file.metadata()?.len());
+        let mut reader = BufReader::new(file);
+
+        let header: RecordingHeader = options.deserialize_from(&mut reader).map_err(malformed)?;
+        if header.protocol_version != PROTOCOL_VERSION {
+            return Err(TransportError::Malformed(format!(
+                "recorded with protocol version {} (roth {}), expected {}",
+                header.protocol_version, header.roth_version, PROTOCOL_VERSION
+            )));
+        }
+
+        let mut frames = vec![];
+        loop {
+            match options.deserialize_from(&mut reader) {
+                Ok(frame) => frames.push(frame),
+                Err(err) => match *err {
+                    bincode::ErrorKind::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                        break
+                    }
+                    _ => return Err(malformed(err)),
+                },
+            }
+        }
+
+        Ok(Self { frames })
+    }
+
+    /// Decodes every frame, in the order they were recorded.
+    pub fn messages(&self) -> Result<Vec<(Duration, RecordedMessage)>, TransportError> {
+        let mut seen_hello = false;
+        let mut seen_reply = false;
+
+        self.frames
+            .iter()
+            .map(|recorded| {
+                let frame = &recorded.frame;
+                let message = match recorded.direction {
+                    Direction::RuntimeToEditor if !seen_hello => {
+                        seen_hello = true;
+                        RecordedMessage::Hello(bincode::deserialize(frame).map_err(malformed)?)
+                    }
+                    Direction::EditorToRuntime if !seen_reply => {
+                        seen_reply = true;
+                        RecordedMessage::HandshakeReply(
+                            bincode::deserialize(frame).map_err(malformed)?,
+                        )
+                    }
+                    Direction::RuntimeToEditor => {
+                        RecordedMessage::Response(bincode::deserialize(frame).map_err(malformed)?)
+                    }
+                    Direction::EditorToRuntime => {
+                        RecordedMessage::Request(bincode::deserialize(frame).map_err(malformed)?)
+                    }
+                };
+                Ok((recorded.elapsed, message))
+            })
+            .collect()
+    }
+}
+
+/// Stands in for the other end of a recorded session: plays back what it sent with the recorded
+/// timing and drops everything sent to it. Disconnects once the recording runs out.
+pub struct ReplayTransport {
+    frames: Mutex<VecDeque<RecordedFrame>>,
+    started: Instant,
+    immediate: bool,
+}
+
+impl ReplayTransport {
+    /// `side` is the end that is being fed, e.g. [`Side::Runtime`] replays what the editor sent.
+    pub fn new(recording: Recording, side: Side) -> Self {
+        Self {
+            frames: Mutex::new(
+                recording
+                    .frames
+                    .into_iter()
+                    .filter(|it| it.direction == side.incoming())
+                    .collect(),
+            ),
+            started: Instant::now(),
+            immediate: false,
+        }
+    }
+
+    /// Hands out frames as soon as they're asked for instead of waiting for their recorded time.
+    pub fn immediate(mut self) -> Self {
+        self.immediate = true;
+        self
+    }
+
+    fn due_in(&self, frame: &RecordedFrame) -> Duration {
+        if self.immediate {
+            return Duration::ZERO;
+        }
+        frame.elapsed.saturating_sub(self.started.elapsed())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, _frame: &[u8]) -> Result<(), TransportError> {
+        Ok(())
+    }
+
+    fn recv(&self) -> Result<Vec<u8>, TransportError> {
+        let mut frames = self.frames.lock().unwrap();
+        let frame = frames.pop_front().ok_or(TransportError::Disconnected)?;
+        thread::sleep(self.due_in(&frame));
+        Ok(frame.frame)
+    }
+
+    fn try_recv(&self) -> Result<Option<Vec<u8>>, TransportError> {
+        let mut frames = self.frames.lock().unwrap();
+        match frames.front() {
+            None => Err(TransportError::Disconnected),
+            Some(frame) if self.due_in(frame).is_zero() => {
+                Ok(frames.pop_front().map(|it| it.frame))
+            }
+            Some(_) => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::{
+    ecs::reflect::{ReflectComponent, ReflectResource},
+    reflect::{
+        std_traits::ReflectDefault, ReflectSerialize, TypeInfo, TypeRegistration, TypeRegistry,
+        VariantInfo,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Everything the runtime's `AppTypeRegistry` knows, the answer to `GetTypeRegistry`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeRegistrySchema {
+    /// sorted by type path
+    pub types: Vec<TypeSchema>,
+}
+
+impl TypeRegistrySchema {
+    pub fn from_registry(registry: &TypeRegistry) -> Self {
+        let mut types = registry
+            .iter()
+            .map(TypeSchema::from_registration)
+            .collect::<Vec<_>>();
+        types.sort_by(|a, b| a.type_path.cmp(&b.type_path));
+        Self { types }
+    }
+
+    pub fn get(&self, type_path: &str) -> Option<&TypeSchema> {
+        self.types
+            .binary_search_by(|it| it.type_path.as_str().cmp(type_path))
+            .ok()
+            .map(|index| &self.types[index])
+    }
+
+    /// Types that can be inserted on an entity.
+    pub fn components(&self) -> impl Iterator<Item = &TypeSchema> {
+        self.types.iter().filter(|it| it.reflect_component)
+    }
+
+    /// Types that can be inserted as a resource.
+    pub fn resources(&self) -> impl Iterator<Item = &TypeSchema> {
+        self.types.iter().filter(|it| it.reflect_resource)
+    }
+}
+
+/// Shape of a single registered type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeSchema {
+    /// same as [`crate::ComponentData::type_name`] for components
+    pub type_path: String,
+    pub short_name: String,
+    pub kind: TypeKind,
+    pub reflect_component: bool,
+    pub reflect_resource: bool,
+    /// whether it can be default constructed, e.g. when adding it to an entity
+    pub reflect_default: bool,
+    /// whether the type uses its own serde implementation, its [`crate::ReflectValue`] then
+    /// follows that instead of the shape in `kind`
+    pub reflect_serialize: bool,
+}
+
+impl TypeSchema {
+    pub fn from_registration(registration: &TypeRegistration) -> Self {
+        let type_info = registration.type_info();
+        Self {
+            type_path: type_info.type_path().to_string(),
+            short_name: bevy::utils::get_short_name(type_info.type_path()),
+            kind: TypeKind::from_type_info(type_info),
+            reflect_component: registration.data::<ReflectComponent>().is_some(),
+            reflect_resource: registration.data::<ReflectResource>().is_some(),
+            reflect_default: registration.data::<ReflectDefault>().is_some(),
+            reflect_serialize: registration.data::<ReflectSerialize>().is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TypeKind {
+    Struct {
+        fields: Vec<FieldSchema>,
+    },
+    /// type paths of the fields
+    TupleStruct {
+        fields: Vec<String>,
+    },
+    Tuple {
+        fields: Vec<String>,
+    },
+    List {
+        item: String,
+    },
+    Array {
+        item: String,
+        len: usize,
+    },
+    Map {
+        key: String,
+        value: String,
+    },
+    Enum {
+        variants: Vec<VariantSchema>,
+    },
+    /// opaque type like a primitive or `String`
+    Value,
+}
+
+impl TypeKind {
+    fn from_type_info(type_info: &TypeInfo) -> Self {
+        match type_info {
+            TypeInfo::Struct(info) => TypeKind::Struct {
+                fields: info
+                    .iter()
+                    .map(|field| FieldSchema {
+                        name: field.name().to_string(),
+                        type_path: field.type_path().to_string(),
+                    })
+                    .collect(),
+            },
+            TypeInfo::TupleStruct(info) => TypeKind::TupleStruct {
+                fields: info.iter().map(|it| it.type_path().to_string()).collect(),
+            },
+            TypeInfo::Tuple(info) => TypeKind::Tuple {
+                fields: info.iter().map(|it| it.type_path().to_string()).collect(),
+            },
+            TypeInfo::List(info) => TypeKind::List {
+                item: info.item_type_path_table().path().to_string(),
+            },
+            TypeInfo::Array(info) => TypeKind::Array {
+                item: info.item_type_path_table().path().to_string(),
+                len: info.capacity(),
+            },
+            TypeInfo::Map(info) => TypeKind::Map {
+                key: info.key_type_path_table().path().to_string(),
+                value: info.value_type_path_table().path().to_string(),
+            },
+            TypeInfo::Enum(info) => TypeKind::Enum {
+                variants: info.iter().map(VariantSchema::from_variant_info).collect(),
+            },
+            TypeInfo::Value(_) => TypeKind::Value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    pub type_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum VariantSchema {
+    Unit {
+        name: String,
+    },
+    Tuple {
+        name: String,
+        fields: Vec<String>,
+    },
+    Struct {
+        name: String,
+        fields: Vec<FieldSchema>,
+    },
+}
+
+impl VariantSchema {
+    fn from_variant_info(info: &VariantInfo) -> Self {
+        match info {
+            VariantInfo::Unit(info) => VariantSchema::Unit {
+                name: info.name().to_string(),
+            },
+            VariantInfo::Tuple(info) => VariantSchema::Tuple {
+                name: info.name().to_string(),
+                fields: info.iter().map(|it| it.type_path().to_string()).collect(),
+            },
+            VariantInfo::Struct(info) => VariantSchema::Struct {
+                name: info.name().to_string(),
+                fields: info
+                    .iter()
+                    .map(|field| FieldSchema {
+                        name: field.name().to_string(),
+                        type_path: field.type_path().to_string(),
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            VariantSchema::Unit { name }
+            | VariantSchema::Tuple { name, .. }
+            | VariantSchema::Struct { name, .. } => name,
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    net::{Shutdown, TcpListener, TcpStream},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+};
+
+use ipc_channel::ipc::{self, IpcBytesReceiver, IpcBytesSender, IpcOneShotServer, IpcSender};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::recording::{Recording, ReplayTransport, Side};
+
+/// The largest frame sent or accepted over TCP, so a corrupt length prefix can't make the other
+/// side allocate gigabytes.
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransportError {
+    /// The other side went away.
+    Disconnected,
+    Io(String),
+    /// A frame that couldn't be encoded or decoded.
+    Malformed(String),
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::Disconnected => write!(f, "the other side disconnected"),
+            TransportError::Io(err) => write!(f, "transport failed: {}", err),
+            TransportError::Malformed(err) => write!(f, "malformed message: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<io::Error> for TransportError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe => TransportError::Disconnected,
+            _ => TransportError::Io(err.to_string()),
+        }
+    }
+}
+
+/// Moves opaque frames between the editor and the runtime, frames arrive whole and in order.
+pub trait Transport: Send + Sync {
+    fn send(&self, frame: &[u8]) -> Result<(), TransportError>;
+    /// Blocks until the next frame arrives.
+    fn recv(&self) -> Result<Vec<u8>, TransportError>;
+    /// Returns `Ok(None)` when no frame is waiting.
+    fn try_recv(&self) -> Result<Option<Vec<u8>>, TransportError>;
+}
+
+pub fn send_message<T: Serialize>(
+    transport: &dyn Transport,
+    msg: &T,
+) -> Result<(), TransportError> {
+    let frame =
+        bincode::serialize(msg).map_err(|err| TransportError::Malformed(err.to_string()))?;
+    transport.send(&frame)
+}
+
+pub fn recv_message<T: DeserializeOwned>(transport: &dyn Transport) -> Result<T, TransportError> {
+    decode(&transport.recv()?)
+}
+
+fn decode<T: DeserializeOwned>(frame: &[u8]) -> Result<T, TransportError> {
+    bincode::deserialize(frame).map_err(|err| TransportError::Malformed(err.to_string()))
+}
+
+/// Typed view of a [`Transport`] that sends `Tx` and receives `Rx`.
+pub struct Connection<Tx, Rx> {
+    transport: Arc<dyn Transport>,
+    _marker: PhantomData<fn(Tx) -> Rx>,
+}
+
+impl<Tx, Rx> Clone for Connection<Tx, Rx> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Tx: Serialize, Rx: DeserializeOwned> Connection<Tx, Rx> {
+    pub fn new(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn send(&self, msg: &Tx) -> Result<(), TransportError> {
+        send_message(&*self.transport, msg)
+    }
+
+    pub fn recv(&self) -> Result<Rx, TransportError> {
+        recv_message(&*self.transport)
+    }
+
+    pub fn try_recv(&self) -> Result<Option<Rx>, TransportError> {
+        self.transport
+            .try_recv()?
+            .map(|frame| decode(&frame))
+            .transpose()
+    }
+}
+
+/// Which [`Transport`] to use, the runtime gets it passed as `--roth-transport`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransportKind {
+    /// `ipc_channel`, only works when both sides run on the same machine
+    #[default]
+    Ipc,
+    /// length prefixed frames over a tcp socket
+    Tcp,
+    /// plays back a session written by a [`crate::recording::Recorder`], the address is the
+    /// path of the recording
+    Replay,
+}
+
+impl FromStr for TransportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ipc" => Ok(TransportKind::Ipc),
+            "tcp" => Ok(TransportKind::Tcp),
+            "replay" => Ok(TransportKind::Replay),
+            _ => Err(format!(
+                "unknown transport `{}`, expected `ipc`, `tcp` or `replay`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportKind::Ipc => write!(f, "ipc"),
+            TransportKind::Tcp => write!(f, "tcp"),
+            TransportKind::Replay => write!(f, "replay"),
+        }
+    }
+}
+
+/// The editor's side of setting up a connection, the runtime [`connect`]s to it.
+pub enum TransportListener {
+    Ipc(IpcOneShotServer<(IpcBytesSender, IpcBytesReceiver)>),
+    Tcp(TcpListener),
+    /// nothing connects to it, accepting plays back what the runtime sent in the recording
+    Replay(Recording),
+}
+
+impl TransportListener {
+    /// Returns the listener and the address the runtime has to connect to. `address` is what the
+    /// tcp listener binds to, use port 0 to pick any free port, or the recording to replay.
+    pub fn bind(kind: TransportKind, address: &str) -> Result<(Self, String), TransportError> {
+        match kind {
+            TransportKind::Ipc => {
+                let (server, name) = IpcOneShotServer::new()?;
+                Ok((TransportListener::Ipc(server), name))
+            }
+            TransportKind::Tcp => {
+                let listener = TcpListener::bind(address)?;
+                let address = listener.local_addr()?.to_string();
+                Ok((TransportListener::Tcp(listener), address))
+            }
+            TransportKind::Replay => Ok((
+                TransportListener::Replay(Recording::open(address)?),
+                address.to_string(),
+            )),
+        }
+    }
+
+    /// Blocks until the runtime connected.
+    pub fn accept(self) -> Result<Arc<dyn Transport>, TransportError> {
+        match self {
+            TransportListener::Ipc(server) => {
+                let (_, (sender, receiver)) = server.accept().map_err(|err| match *err {
+                    bincode::ErrorKind::Io(err) => TransportError::from(err),
+                    err => TransportError::Malformed(err.to_string()),
+                })?;
+                Ok(Arc::new(IpcTransport::new(sender, receiver)))
+            }
+            TransportListener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                Ok(Arc::new(TcpTransport::new(stream)?))
+            }
+            TransportListener::Replay(recording) => {
+                Ok(Arc::new(ReplayTransport::new(recording, Side::Editor)))
+            }
+        }
+    }
+}
+
+/// The runtime's side of setting up a connection, `address` is what [`TransportListener::bind`] returned.
+pub fn connect(kind: TransportKind, address: &str) -> Result<Arc<dyn Transport>, TransportError> {
+    match kind {
+        TransportKind::Ipc => Ok(Arc::new(IpcTransport::connect(address)?)),
+        TransportKind::Tcp => Ok(Arc::new(TcpTransport::new(TcpStream::connect(address)?)?)),
+        TransportKind::Replay => Ok(Arc::new(ReplayTransport::new(
+            Recording::open(address)?,
+            Side::Runtime,
+        ))),
+    }
+}
+
+pub struct IpcTransport {
+    // the ipc types aren't `Sync` on every platform
+    sender: Mutex<IpcBytesSender>,
+    receiver: Mutex<IpcBytesReceiver>,
+}
+
+impl IpcTransport {
+    fn new(sender: IpcBytesSender, receiver: IpcBytesReceiver) -> Self {
+        Self {
+            sender: Mutex::new(sender),
+            receiver: Mutex::new(receiver),
+        }
+    }
+
+    /// Creates both channels and hands the editor its ends through the one shot server `server_name`.
+    pub fn connect(server_name: &str) -> Result<Self, TransportError> {
+        let (editor_to_runtime, runtime_receiver) = ipc::bytes_channel()?;
+        let (runtime_to_editor, editor_receiver) = ipc::bytes_channel()?;
+
+        let server: IpcSender<(IpcBytesSender, IpcBytesReceiver)> =
+            IpcSender::connect(server_name.to_string())?;
+        server
+            .send((editor_to_runtime, editor_receiver))
+            .map_err(|err| TransportError::Io(err.to_string()))?;
+
+        Ok(Self::new(runtime_to_editor, runtime_receiver))
+    }
+}
+
+impl Transport for IpcTransport {
+    fn send(&self, frame: &[u8]) -> Result<(), TransportError> {
+        self.sender
+            .lock()
+            .unwrap()
+            .send(frame)
+            .map_err(TransportError::from)
+    }
+
+    fn recv(&self) -> Result<Vec<u8>, TransportError> {
+        self.receiver
+            .lock()
+            .unwrap()
+            .recv()
+            .map_err(|err| match err {
+                ipc::IpcError::Disconnected => TransportError::Disconnected,
+                err => TransportError::Io(format!("{:?}", err)),
+            })
+    }
+
+    fn try_recv(&self) -> Result<Option<Vec<u8>>, TransportError> {
+        match self.receiver.lock().unwrap().try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(ipc::TryRecvError::Empty) => Ok(None),
+            Err(ipc::TryRecvError::IpcError(ipc::IpcError::Disconnected)) => {
+                Err(TransportError::Disconnected)
+            }
+            Err(ipc::TryRecvError::IpcError(err)) => Err(TransportError::Io(format!("{:?}", err))),
+        }
+    }
+}
+
+/// Both ends of a connection within one process, e.g. to run the editor plugin in a test.
+pub struct MemoryTransport {
+    sender: Mutex<mpsc::Sender<Vec<u8>>>,
+    receiver: Mutex<mpsc::Receiver<Vec<u8>>>,
+}
+
+impl MemoryTransport {
+    pub fn pair() -> (Self, Self) {
+        let (a_sender, b_receiver) = mpsc::channel();
+        let (b_sender, a_receiver) = mpsc::channel();
+        (
+            Self {
+                sender: Mutex::new(a_sender),
+                receiver: Mutex::new(a_receiver),
+            },
+            Self {
+                sender: Mutex::new(b_sender),
+                receiver: Mutex::new(b_receiver),
+            },
+        )
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, frame: &[u8]) -> Result<(), TransportError> {
+        self.sender
+            .lock()
+            .unwrap()
+            .send(frame.to_vec())
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    fn recv(&self) -> Result<Vec<u8>, TransportError> {
+        self.receiver
+            .lock()
+            .unwrap()
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    fn try_recv(&self) -> Result<Option<Vec<u8>>, TransportError> {
+        match self.receiver.lock().unwrap().try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(TransportError::Disconnected),
+        }
+    }
+}
+
+/// Every frame is prefixed with its length as a little endian `u32`.
+pub struct TcpTransport {
+    writer: Mutex<TcpStream>,
+    /// filled by a thread that reads the socket, so `try_recv` never sees half a frame
+    frames: Mutex<mpsc::Receiver<Result<Vec<u8>, TransportError>>>,
+    /// set once the reader thread failed, it may not have dropped its sender yet
+    failed: AtomicBool,
+}
+
+impl TcpTransport {
+    pub fn new(stream: TcpStream) -> Result<Self, TransportError> {
+        stream.set_nodelay(true)?;
+        let mut reader = stream.try_clone()?;
+        let (sender, frames) = mpsc::channel();
+
+        thread::spawn(move || loop {
+            let frame = read_frame(&mut reader);
+            let failed = frame.is_err();
+            if sender.send(frame).is_err() || failed {
+                break;
+            }
+        });
+
+        Ok(Self {
+            writer: Mutex::new(stream),
+            frames: Mutex::new(frames),
+            failed: AtomicBool::new(false),
+        })
+    }
+
+    fn check_failed(
+        &self,
+        frame: Result<Vec<u8>, TransportError>,
+    ) -> Result<Vec<u8>, TransportError> {
+        if frame.is_err() {
+            self.failed.store(true, Ordering::Relaxed);
+        }
+        frame
+    }
+}
+
+fn frame_too_large(len: usize) -> TransportError {
+    TransportError::Malformed(format!(
+        "frame of {} bytes, at most {} are allowed",
+        len, MAX_FRAME_LEN
+    ))
+}
+
+fn read_frame(reader: &mut impl Read) -> Result<Vec<u8>, TransportError> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(frame_too_large(len));
+    }
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame)?;
+    Ok(frame)
+}
+
+impl Transport for TcpTransport {
+    fn send(&self, frame: &[u8]) -> Result<(), TransportError> {
+        if frame.len() > MAX_FRAME_LEN {
+            return Err(frame_too_large(frame.len()));
+        }
+        let len = frame.len() as u32;
+        let mut writer = self.writer.lock().unwrap();
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(frame)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn recv(&self) -> Result<Vec<u8>, TransportError> {
+        if self.failed.load(Ordering::Relaxed) {
+            return Err(TransportError::Disconnected);
+        }
+        let frame = self.frames.lock().unwrap().recv();
+        self.check_failed(frame.unwrap_or(Err(TransportError::Disconnected)))
+    }
+
+    fn try_recv(&self) -> Result<Option<Vec<u8>>, TransportError> {
+        if self.failed.load(Ordering::Relaxed) {
+            return Err(TransportError::Disconnected);
+        }
+        match self.frames.lock().unwrap().try_recv() {
+            Ok(frame) => self.check_failed(frame).map(Some),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(TransportError::Disconnected),
+        }
+    }
+}
+
+impl Drop for TcpTransport {
+    fn drop(&mut self) {
+        // unblocks the reader thread
+        let _ = self.writer.lock().unwrap().shutdown(Shutdown::Both);
+    }
+}
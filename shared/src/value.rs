@@ -0,0 +1,771 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use bevy::reflect::{
+    serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    Reflect, TypeRegistration, TypeRegistry,
+};
+use serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, Visitor},
+    ser::{self, Serialize},
+};
+
+/// Self describing copy of a reflected value, the format component and resource values travel in.
+///
+/// It is produced by running bevy's `TypedReflectSerializer` into a [`ValueSerializer`] and read
+/// back by running `TypedReflectDeserializer` on it, so unlike RON or JSON it keeps every enum
+/// variant, field name and number type and round-trips losslessly in both directions.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ReflectValue {
+    Unit,
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    Bytes(Vec<u8>),
+    Option(Option<Box<ReflectValue>>),
+    Newtype(Box<ReflectValue>),
+    /// lists and sets
+    Seq(Vec<ReflectValue>),
+    /// tuples, tuple structs and arrays
+    Tuple(Vec<ReflectValue>),
+    Map(Vec<(ReflectValue, ReflectValue)>),
+    Struct(Vec<(String, ReflectValue)>),
+    Enum {
+        variant: String,
+        value: VariantValue,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum VariantValue {
+    Unit,
+    Newtype(Box<ReflectValue>),
+    Tuple(Vec<ReflectValue>),
+    Struct(Vec<(String, ReflectValue)>),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ValueError(pub String);
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+impl ser::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
+
+impl de::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
+
+impl ReflectValue {
+    /// Serializes `value` through bevy's `TypedReflectSerializer`.
+    pub fn from_reflect(value: &dyn Reflect, registry: &TypeRegistry) -> Result<Self, ValueError> {
+        TypedReflectSerializer::new(value, registry).serialize(ValueSerializer)
+    }
+
+    /// Deserializes a dynamic value of the type in `registration` through bevy's
+    /// `TypedReflectDeserializer`, use `FromReflect` or `apply` to turn it into the concrete type.
+    pub fn to_reflect(
+        &self,
+        registration: &TypeRegistration,
+        registry: &TypeRegistry,
+    ) -> Result<Box<dyn Reflect>, ValueError> {
+        TypedReflectDeserializer::new(registration, registry).deserialize(self.clone())
+    }
+
+    /// Looks up a field of a struct or struct variant.
+    pub fn field(&self, name: &str) -> Option<&ReflectValue> {
+        let fields = match self {
+            ReflectValue::Struct(fields) => fields,
+            ReflectValue::Enum {
+                value: VariantValue::Struct(fields),
+                ..
+            } => fields,
+            _ => return None,
+        };
+
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Looks up the value at `path`, a reflect path as bevy's `GetPath` takes it: struct fields by
+    /// name, tuple fields as `.0` and list items as `[0]`, e.g. `translation.x`. Indices work with
+    /// either syntax, arrays are serialized as tuples but bevy only accepts `[0]` for them.
+    pub fn path_mut(&mut self, path: &str) -> Option<&mut ReflectValue> {
+        path_segments(path)?
+            .into_iter()
+            .try_fold(self, |value, segment| value.child_mut(segment))
+    }
+
+    fn child_mut(&mut self, segment: &str) -> Option<&mut ReflectValue> {
+        match self {
+            ReflectValue::Struct(fields)
+            | ReflectValue::Enum {
+                value: VariantValue::Struct(fields),
+                ..
+            } => fields
+                .iter_mut()
+                .find(|(field, _)| field == segment)
+                .map(|(_, value)| value),
+            ReflectValue::Seq(items)
+            | ReflectValue::Tuple(items)
+            | ReflectValue::Enum {
+                value: VariantValue::Tuple(items),
+                ..
+            } => items.get_mut(segment.parse::<usize>().ok()?),
+            ReflectValue::Newtype(value)
+            | ReflectValue::Option(Some(value))
+            | ReflectValue::Enum {
+                value: VariantValue::Newtype(value),
+                ..
+            } if segment == "0" => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ReflectValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Splits `translation.x` or `.lights[2]` into its field names and indices, `None` when it's malformed.
+fn path_segments(path: &str) -> Option<Vec<&str>> {
+    let mut segments = vec![];
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let end = index.find(']')?;
+            segments.push(&index[..end]);
+            rest = &index[end + 1..];
+            continue;
+        }
+
+        let field = rest.strip_prefix('.').unwrap_or(rest);
+        let end = field.find(['.', '[']).unwrap_or(field.len());
+        if end == 0 {
+            return None;
+        }
+        segments.push(&field[..end]);
+        rest = &field[end..];
+    }
+    Some(segments)
+}
+
+/// Floats are hashed by their bits, so values that compare equal hash equal except for `NaN`s.
+impl Hash for ReflectValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            ReflectValue::Unit => {}
+            ReflectValue::Bool(v) => v.hash(state),
+            ReflectValue::I8(v) => v.hash(state),
+            ReflectValue::I16(v) => v.hash(state),
+            ReflectValue::I32(v) => v.hash(state),
+            ReflectValue::I64(v) => v.hash(state),
+            ReflectValue::I128(v) => v.hash(state),
+            ReflectValue::U8(v) => v.hash(state),
+            ReflectValue::U16(v) => v.hash(state),
+            ReflectValue::U32(v) => v.hash(state),
+            ReflectValue::U64(v) => v.hash(state),
+            ReflectValue::U128(v) => v.hash(state),
+            ReflectValue::F32(v) => v.to_bits().hash(state),
+            ReflectValue::F64(v) => v.to_bits().hash(state),
+            ReflectValue::Char(v) => v.hash(state),
+            ReflectValue::String(v) => v.hash(state),
+            ReflectValue::Bytes(v) => v.hash(state),
+            ReflectValue::Option(v) => v.hash(state),
+            ReflectValue::Newtype(v) => v.hash(state),
+            ReflectValue::Seq(v) | ReflectValue::Tuple(v) => v.hash(state),
+            ReflectValue::Map(v) => v.hash(state),
+            ReflectValue::Struct(v) => v.hash(state),
+            ReflectValue::Enum { variant, value } => {
+                variant.hash(state);
+                value.hash(state);
+            }
+        }
+    }
+}
+
+impl Hash for VariantValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            VariantValue::Unit => {}
+            VariantValue::Newtype(v) => v.hash(state),
+            VariantValue::Tuple(v) => v.hash(state),
+            VariantValue::Struct(v) => v.hash(state),
+        }
+    }
+}
+
+/// A `serde::Serializer` that captures whatever is serialized into a [`ReflectValue`].
+pub struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeSeq;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeStruct;
+
+    fn serialize_bool(self, v: bool) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Option(None))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Option(Some(Box::new(
+            value.serialize(ValueSerializer)?,
+        ))))
+    }
+
+    fn serialize_unit(self) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Unit)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Enum {
+            variant: variant.to_string(),
+            value: VariantValue::Unit,
+        })
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Newtype(Box::new(
+            value.serialize(ValueSerializer)?,
+        )))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Enum {
+            variant: variant.to_string(),
+            value: VariantValue::Newtype(Box::new(value.serialize(ValueSerializer)?)),
+        })
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq, ValueError> {
+        Ok(SerializeSeq::new(SeqKind::Seq, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq, ValueError> {
+        Ok(SerializeSeq::new(SeqKind::Tuple, Some(len)))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq, ValueError> {
+        Ok(SerializeSeq::new(SeqKind::Tuple, Some(len)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq, ValueError> {
+        Ok(SerializeSeq::new(
+            SeqKind::Variant(variant.to_string()),
+            Some(len),
+        ))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, ValueError> {
+        Ok(SerializeMap {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeStruct, ValueError> {
+        Ok(SerializeStruct {
+            variant: None,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStruct, ValueError> {
+        Ok(SerializeStruct {
+            variant: Some(variant.to_string()),
+            fields: Vec::with_capacity(len),
+        })
+    }
+}
+
+enum SeqKind {
+    Seq,
+    Tuple,
+    Variant(String),
+}
+
+pub struct SerializeSeq {
+    kind: SeqKind,
+    items: Vec<ReflectValue>,
+}
+
+impl SerializeSeq {
+    fn new(kind: SeqKind, len: Option<usize>) -> Self {
+        Self {
+            kind,
+            items: Vec::with_capacity(len.unwrap_or_default()),
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> ReflectValue {
+        match self.kind {
+            SeqKind::Seq => ReflectValue::Seq(self.items),
+            SeqKind::Tuple => ReflectValue::Tuple(self.items),
+            SeqKind::Variant(variant) => ReflectValue::Enum {
+                variant,
+                value: VariantValue::Tuple(self.items),
+            },
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeSeq {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+pub struct SerializeMap {
+    entries: Vec<(ReflectValue, ReflectValue)>,
+    key: Option<ReflectValue>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ValueError> {
+        self.key = Some(key.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ValueError("map value serialized before its key".to_string()))?;
+        self.entries.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(ReflectValue::Map(self.entries))
+    }
+}
+
+pub struct SerializeStruct {
+    variant: Option<String>,
+    fields: Vec<(String, ReflectValue)>,
+}
+
+impl SerializeStruct {
+    fn push<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), ValueError> {
+        self.fields
+            .push((key.to_string(), value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> ReflectValue {
+        match self.variant {
+            Some(variant) => ReflectValue::Enum {
+                variant,
+                value: VariantValue::Struct(self.fields),
+            },
+            None => ReflectValue::Struct(self.fields),
+        }
+    }
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeStruct {
+    type Ok = ReflectValue;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<ReflectValue, ValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ReflectValue {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            ReflectValue::Unit => visitor.visit_unit(),
+            ReflectValue::Bool(v) => visitor.visit_bool(v),
+            ReflectValue::I8(v) => visitor.visit_i8(v),
+            ReflectValue::I16(v) => visitor.visit_i16(v),
+            ReflectValue::I32(v) => visitor.visit_i32(v),
+            ReflectValue::I64(v) => visitor.visit_i64(v),
+            ReflectValue::I128(v) => visitor.visit_i128(v),
+            ReflectValue::U8(v) => visitor.visit_u8(v),
+            ReflectValue::U16(v) => visitor.visit_u16(v),
+            ReflectValue::U32(v) => visitor.visit_u32(v),
+            ReflectValue::U64(v) => visitor.visit_u64(v),
+            ReflectValue::U128(v) => visitor.visit_u128(v),
+            ReflectValue::F32(v) => visitor.visit_f32(v),
+            ReflectValue::F64(v) => visitor.visit_f64(v),
+            ReflectValue::Char(v) => visitor.visit_char(v),
+            ReflectValue::String(v) => visitor.visit_string(v),
+            ReflectValue::Bytes(v) => visitor.visit_byte_buf(v),
+            ReflectValue::Option(None) => visitor.visit_none(),
+            ReflectValue::Option(Some(v)) => visitor.visit_some(*v),
+            ReflectValue::Newtype(v) => visitor.visit_newtype_struct(*v),
+            ReflectValue::Seq(items) | ReflectValue::Tuple(items) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(items.into_iter()))
+            }
+            ReflectValue::Map(entries) => {
+                visitor.visit_map(de::value::MapDeserializer::new(entries.into_iter()))
+            }
+            ReflectValue::Struct(fields) => visitor.visit_map(de::value::MapDeserializer::new(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (ReflectValue::String(key), value)),
+            )),
+            ReflectValue::Enum { variant, value } => {
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            ReflectValue::Option(None) | ReflectValue::Unit => visitor.visit_none(),
+            ReflectValue::Option(Some(v)) => visitor.visit_some(*v),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            ReflectValue::Newtype(v) => visitor.visit_newtype_struct(*v),
+            other => visitor.visit_newtype_struct(other),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            ReflectValue::Enum { variant, value } => {
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            ReflectValue::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: VariantValue::Unit,
+            }),
+            other => Err(de::Error::custom(format_args!(
+                "expected an enum, found {other:?}"
+            ))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for ReflectValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: VariantValue,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = ValueError;
+    type Variant = VariantValue;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantValue), ValueError> {
+        let variant = seed.deserialize(ReflectValue::String(self.variant))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantValue {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        match self {
+            VariantValue::Unit => Ok(()),
+            other => Err(de::Error::custom(format_args!(
+                "expected a unit variant, found {other:?}"
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, ValueError> {
+        match self {
+            VariantValue::Newtype(value) => seed.deserialize(*value),
+            // bevy serializes single field tuple variants as newtypes, but accept both
+            VariantValue::Tuple(mut fields) if fields.len() == 1 => {
+                seed.deserialize(fields.remove(0))
+            }
+            other => Err(de::Error::custom(format_args!(
+                "expected a newtype variant, found {other:?}"
+            ))),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            VariantValue::Tuple(fields) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(fields.into_iter()))
+            }
+            VariantValue::Newtype(value) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(std::iter::once(*value)))
+            }
+            other => Err(de::Error::custom(format_args!(
+                "expected a tuple variant, found {other:?}"
+            ))),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            VariantValue::Struct(fields) => visitor.visit_map(de::value::MapDeserializer::new(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (ReflectValue::String(key), value)),
+            )),
+            other => Err(de::Error::custom(format_args!(
+                "expected a struct variant, found {other:?}"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use roth_shared::{launch::LaunchArgs, transport::TransportKind};
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|it| it.to_string()).collect()
+}
+
+fn no_vars(_: &str) -> Option<String> {
+    None
+}
+
+#[test]
+fn standalone_without_flags_or_vars() {
+    let parsed = LaunchArgs::parse(&args(&["game", "--fullscreen"]), no_vars).unwrap();
+    assert_eq!(parsed, None);
+}
+
+#[test]
+fn flags() {
+    let parsed = LaunchArgs::parse(
+        &args(&[
+            "game",
+            "--fullscreen",
+            "--roth-transport",
+            "tcp",
+            "--roth-ipc=127.0.0.1:4000",
+            "--roth-window-id",
+            "42",
+        ]),
+        no_vars,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(parsed.transport, TransportKind::Tcp);
+    assert_eq!(parsed.ipc, "127.0.0.1:4000");
+    assert_eq!(parsed.window_id, Some(42));
+    assert_eq!(parsed.record, None);
+}
+
+#[test]
+fn vars_are_a_fallback() {
+    let vars = |name: &str| match name {
+        "ROTH_IPC" => Some("from-var".to_string()),
+        "ROTH_TRANSPORT" => Some("tcp".to_string()),
+        "ROTH_RUNTIME_RECORD" => Some("session.roth".to_string()),
+        _ => None,
+    };
+    let parsed = LaunchArgs::parse(&args(&["game", "--roth-ipc", "from-flag"]), vars)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(parsed.ipc, "from-flag");
+    assert_eq!(parsed.transport, TransportKind::Tcp);
+    assert_eq!(parsed.record, Some(PathBuf::from("session.roth")));
+}
+
+#[test]
+fn round_trips_through_args() {
+    let mut launch = LaunchArgs::new(TransportKind::Ipc, "server-name");
+    launch.window_id = Some(7);
+    launch.record = Some(PathBuf::from("session.roth"));
+
+    let mut command_line = args(&["game"]);
+    command_line.extend(launch.to_args());
+    // bevy's winit backend still reads the window id from where it always was
+    assert_eq!(command_line[1..3], args(&["--window-id", "7"]));
+    assert_eq!(
+        LaunchArgs::parse(&command_line, no_vars).unwrap(),
+        Some(launch)
+    );
+}
+
+#[test]
+fn rejects_invalid_values() {
+    assert!(LaunchArgs::parse(&args(&["game", "--roth-transport", "tcp"]), no_vars).is_err());
+    assert!(LaunchArgs::parse(
+        &args(&["game", "--roth-ipc", "a", "--roth-transport", "udp"]),
+        no_vars
+    )
+    .is_err());
+    assert!(LaunchArgs::parse(
+        &args(&["game", "--roth-ipc", "a", "--roth-window-id", "main"]),
+        no_vars
+    )
+    .is_err());
+}
@@ -0,0 +1,162 @@
+use std::{sync::Arc, thread, time::Duration};
+
+use roth_shared::{
+    handshake::{Capabilities, HandshakeReply, Hello, RuntimeInfo},
+    recording::{RecordedMessage, Recorder, Recording, RecordingTransport, ReplayTransport, Side},
+    transport::{self, Connection, Transport, TransportError, TransportKind, TransportListener},
+    EditorToRuntimeMsg, Request, RequestId, Response, RuntimeToEditorMsg,
+};
+
+fn runtime_hello() -> Hello {
+    Hello::runtime(
+        Capabilities::ENTITIES,
+        RuntimeInfo {
+            name: "test".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    )
+}
+
+/// Records a short session on the editor's side of a tcp connection.
+fn record_session(path: &std::path::Path) {
+    let (listener, address) = TransportListener::bind(TransportKind::Tcp, "127.0.0.1:0").unwrap();
+    let runtime = thread::spawn(move || {
+        let transport = transport::connect(TransportKind::Tcp, &address).unwrap();
+        transport::send_message(&*transport, &runtime_hello()).unwrap();
+        transport::recv_message::<HandshakeReply>(&*transport)
+            .unwrap()
+            .unwrap();
+
+        let connection = Connection::<Response, Request>::new(transport);
+        let request = connection.recv().unwrap();
+        connection
+            .send(&Response {
+                request_id: Some(request.id),
+                msg: RuntimeToEditorMsg::Entities {
+                    entities: vec![],
+                    hierarchy: vec![],
+                },
+            })
+            .unwrap();
+    });
+
+    let recorder = Arc::new(Recorder::create(path).unwrap());
+    let editor: Arc<dyn Transport> = Arc::new(RecordingTransport::new(
+        listener.accept().unwrap(),
+        recorder,
+        Side::Editor,
+    ));
+    let hello = transport::recv_message::<Hello>(&*editor).unwrap();
+    transport::send_message::<HandshakeReply>(&*editor, &Ok(hello)).unwrap();
+
+    let connection = Connection::<Request, Response>::new(editor);
+    thread::sleep(Duration::from_millis(20));
+    connection
+        .send(&Request {
+            id: RequestId(1),
+            msg: EditorToRuntimeMsg::GetEntities,
+        })
+        .unwrap();
+    connection.recv().unwrap();
+    runtime.join().unwrap();
+}
+
+fn recording_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("roth-{}-{}.rec", name, std::process::id()))
+}
+
+#[test]
+fn records_every_message() {
+    let path = recording_path("records");
+    record_session(&path);
+
+    let messages = Recording::open(&path).unwrap().messages().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(messages.len(), 4);
+    assert!(matches!(&messages[0].1, RecordedMessage::Hello(hello) if hello == &runtime_hello()));
+    assert!(matches!(
+        &messages[1].1,
+        RecordedMessage::HandshakeReply(Ok(_))
+    ));
+    assert!(matches!(
+        &messages[2].1,
+        RecordedMessage::Request(Request {
+            id: RequestId(1),
+            msg: EditorToRuntimeMsg::GetEntities
+        })
+    ));
+    assert!(matches!(
+        &messages[3].1,
+        RecordedMessage::Response(Response {
+            request_id: Some(RequestId(1)),
+            msg: RuntimeToEditorMsg::Entities { .. }
+        })
+    ));
+    assert!(messages[2].0 >= Duration::from_millis(20));
+    assert!(messages.windows(2).all(|it| it[0].0 <= it[1].0));
+}
+
+#[test]
+fn replays_into_the_runtime() {
+    let path = recording_path("runtime");
+    record_session(&path);
+    let recording = Recording::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let replay = Arc::new(ReplayTransport::new(recording, Side::Runtime));
+    // the runtime's hello goes nowhere, the recorded answer comes back
+    transport::send_message(&*replay, &runtime_hello()).unwrap();
+    assert!(transport::recv_message::<HandshakeReply>(&*replay)
+        .unwrap()
+        .is_ok());
+
+    let connection = Connection::<Response, Request>::new(replay);
+    assert!(connection.try_recv().unwrap().is_none());
+    let request = connection.recv().unwrap();
+    assert!(matches!(request.msg, EditorToRuntimeMsg::GetEntities));
+    assert_eq!(connection.recv().err(), Some(TransportError::Disconnected));
+}
+
+#[test]
+fn replays_into_the_editor() {
+    let path = recording_path("editor");
+    record_session(&path);
+
+    let (listener, _) =
+        TransportListener::bind(TransportKind::Replay, path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let replay = listener.accept().unwrap();
+
+    assert_eq!(
+        transport::recv_message::<Hello>(&*replay).unwrap(),
+        runtime_hello()
+    );
+    let connection = Connection::<Request, Response>::new(replay);
+    let response = connection.recv().unwrap();
+    assert_eq!(response.request_id, Some(RequestId(1)));
+    assert_eq!(connection.recv().err(), Some(TransportError::Disconnected));
+}
+
+#[test]
+fn immediate_replay_does_not_wait() {
+    let path = recording_path("immediate");
+    record_session(&path);
+    let recording = Recording::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let replay = ReplayTransport::new(recording, Side::Runtime).immediate();
+    assert!(replay.try_recv().unwrap().is_some());
+    assert!(replay.try_recv().unwrap().is_some());
+    assert_eq!(replay.try_recv(), Err(TransportError::Disconnected));
+}
+
+#[test]
+fn rejects_files_that_are_not_recordings() {
+    let path = recording_path("garbage");
+    std::fs::write(&path, [0xff; 16]).unwrap();
+    let result = Recording::open(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(TransportError::Malformed(_))));
+}
@@ -0,0 +1,85 @@
+use bevy::{prelude::*, reflect::TypeRegistry};
+use roth_shared::{
+    schema::{TypeKind, VariantSchema},
+    TypeRegistrySchema,
+};
+
+fn schema() -> TypeRegistrySchema {
+    let mut registry = TypeRegistry::default();
+    registry.register::<Transform>();
+    registry.register::<Visibility>();
+    registry.register::<Name>();
+    registry.register::<Vec<f32>>();
+    registry.register::<ClearColor>();
+    TypeRegistrySchema::from_registry(&registry)
+}
+
+#[test]
+fn structs() {
+    let schema = schema();
+    let transform = schema
+        .get("bevy_transform::components::transform::Transform")
+        .unwrap();
+
+    assert_eq!(transform.short_name, "Transform");
+    let TypeKind::Struct { fields } = &transform.kind else {
+        panic!("expected a struct, got {:?}", transform.kind);
+    };
+    let fields = fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.type_path.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [
+            ("translation", "glam::Vec3"),
+            ("rotation", "glam::Quat"),
+            ("scale", "glam::Vec3"),
+        ]
+    );
+    assert!(transform.reflect_component);
+    assert!(transform.reflect_default);
+    assert!(!transform.reflect_resource);
+}
+
+#[test]
+fn enums() {
+    let schema = schema();
+    let visibility = schema
+        .get("bevy_render::view::visibility::Visibility")
+        .unwrap();
+
+    let TypeKind::Enum { variants } = &visibility.kind else {
+        panic!("expected an enum, got {:?}", visibility.kind);
+    };
+    assert!(variants
+        .iter()
+        .all(|variant| matches!(variant, VariantSchema::Unit { .. })));
+    assert_eq!(
+        variants.iter().map(VariantSchema::name).collect::<Vec<_>>(),
+        ["Inherited", "Hidden", "Visible"]
+    );
+}
+
+#[test]
+fn lists() {
+    let schema = schema();
+    let list = schema.get("alloc::vec::Vec<f32>").unwrap();
+
+    assert_eq!(
+        list.kind,
+        TypeKind::List {
+            item: "f32".to_string()
+        }
+    );
+    assert!(!list.reflect_component);
+}
+
+#[test]
+fn components_and_resources() {
+    let schema = schema();
+
+    assert!(schema.components().any(|it| it.short_name == "Name"));
+    assert!(schema.resources().any(|it| it.short_name == "ClearColor"));
+    assert!(!schema.components().any(|it| it.short_name == "ClearColor"));
+}
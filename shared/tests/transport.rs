@@ -0,0 +1,218 @@
+use std::{io::Write, net::TcpStream, sync::Arc, thread, time::Duration};
+
+use bevy::ecs::entity::Entity;
+use roth_shared::{
+    handshake::{Capabilities, HandshakeError, Hello, RuntimeInfo},
+    transport::{
+        self, Connection, MemoryTransport, Transport, TransportError, TransportKind,
+        TransportListener, MAX_FRAME_LEN,
+    },
+    ComponentData, EditorToRuntimeMsg, ReflectValue, Request, RequestId, Response,
+    RuntimeToEditorMsg,
+};
+
+/// Returns the editor's and the runtime's end of a connection.
+fn connect(kind: TransportKind) -> (Arc<dyn Transport>, Arc<dyn Transport>) {
+    let (listener, address) = TransportListener::bind(kind, "127.0.0.1:0").unwrap();
+    let runtime = thread::spawn(move || transport::connect(kind, &address).unwrap());
+    let editor = listener.accept().unwrap();
+    (editor, runtime.join().unwrap())
+}
+
+fn request() -> Request {
+    Request {
+        id: RequestId(7),
+        msg: EditorToRuntimeMsg::InsertComponent {
+            entity: Entity::from_raw(3),
+            component: ComponentData {
+                type_name: "bevy_core::name::Name".to_string(),
+                value: Some(ReflectValue::String("Player".to_string())),
+            },
+        },
+    }
+}
+
+fn round_trip(kind: TransportKind) {
+    let (editor, runtime) = connect(kind);
+
+    let hello = Hello::runtime(
+        Capabilities::ENTITIES,
+        RuntimeInfo {
+            name: "test".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    );
+    transport::send_message(&*runtime, &hello).unwrap();
+    assert_eq!(transport::recv_message::<Hello>(&*editor).unwrap(), hello);
+
+    let editor = Connection::<Request, Response>::new(editor);
+    let runtime = Connection::<Response, Request>::new(runtime);
+
+    assert!(runtime.try_recv().unwrap().is_none());
+    editor.send(&request()).unwrap();
+    let received = runtime.recv().unwrap();
+    assert_eq!(received.id, RequestId(7));
+    assert!(matches!(
+        received.msg,
+        EditorToRuntimeMsg::InsertComponent { entity, .. } if entity == Entity::from_raw(3)
+    ));
+
+    runtime
+        .send(&Response {
+            request_id: Some(received.id),
+            msg: RuntimeToEditorMsg::ComponentInserted {
+                entity: Entity::from_raw(3),
+                type_name: "bevy_core::name::Name".to_string(),
+            },
+        })
+        .unwrap();
+    let response = editor.recv().unwrap();
+    assert_eq!(response.request_id, Some(RequestId(7)));
+}
+
+#[test]
+fn tcp_round_trip() {
+    round_trip(TransportKind::Tcp);
+}
+
+#[test]
+fn ipc_round_trip() {
+    round_trip(TransportKind::Ipc);
+}
+
+#[test]
+fn tcp_frames_arrive_whole_and_in_order() {
+    let (editor, runtime) = connect(TransportKind::Tcp);
+
+    let large = (0..1_000_000).map(|it| it as u8).collect::<Vec<_>>();
+    for i in 0..10u8 {
+        editor.send(&[i]).unwrap();
+        editor.send(&large).unwrap();
+    }
+    editor.send(&[]).unwrap();
+
+    for i in 0..10u8 {
+        assert_eq!(runtime.recv().unwrap(), [i]);
+        assert_eq!(runtime.recv().unwrap(), large);
+    }
+    assert_eq!(runtime.recv().unwrap(), Vec::<u8>::new());
+}
+
+#[test]
+fn tcp_try_recv_waits_for_whole_frames() {
+    let (editor, runtime) = connect(TransportKind::Tcp);
+
+    assert_eq!(runtime.try_recv().unwrap(), None);
+    editor.send(b"hello").unwrap();
+
+    let frame = loop {
+        if let Some(frame) = runtime.try_recv().unwrap() {
+            break frame;
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!(frame, b"hello");
+    assert_eq!(runtime.try_recv().unwrap(), None);
+}
+
+#[test]
+fn tcp_disconnect() {
+    let (editor, runtime) = connect(TransportKind::Tcp);
+
+    drop(editor);
+    assert_eq!(runtime.recv(), Err(TransportError::Disconnected));
+    assert_eq!(runtime.try_recv(), Err(TransportError::Disconnected));
+}
+
+#[test]
+fn tcp_try_recv_keeps_reporting_the_disconnect() {
+    // the reader thread may still be around right after it reported the disconnect, which only
+    // shows up once in a while
+    for _ in 0..100 {
+        let (editor, runtime) = connect(TransportKind::Tcp);
+
+        drop(editor);
+        assert_eq!(runtime.recv(), Err(TransportError::Disconnected));
+        for _ in 0..10 {
+            assert_eq!(runtime.try_recv(), Err(TransportError::Disconnected));
+        }
+    }
+}
+
+#[test]
+fn tcp_frames_larger_than_the_limit_are_refused() {
+    let (listener, address) = TransportListener::bind(TransportKind::Tcp, "127.0.0.1:0").unwrap();
+    let peer = thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        stream
+    });
+    let editor = listener.accept().unwrap();
+    let _peer = peer.join().unwrap();
+
+    assert!(matches!(editor.recv(), Err(TransportError::Malformed(_))));
+    assert_eq!(editor.try_recv(), Err(TransportError::Disconnected));
+
+    let (editor, _runtime) = connect(TransportKind::Tcp);
+    assert!(matches!(
+        editor.send(&vec![0; MAX_FRAME_LEN + 1]),
+        Err(TransportError::Malformed(_))
+    ));
+}
+
+#[test]
+fn memory_pair() {
+    let (editor, runtime) = MemoryTransport::pair();
+
+    assert_eq!(runtime.try_recv().unwrap(), None);
+    editor.send(b"hello").unwrap();
+    runtime.send(b"world").unwrap();
+    assert_eq!(runtime.try_recv().unwrap(), Some(b"hello".to_vec()));
+    assert_eq!(editor.recv().unwrap(), b"world");
+
+    drop(editor);
+    assert_eq!(runtime.recv(), Err(TransportError::Disconnected));
+}
+
+#[test]
+fn malformed_message() {
+    let (editor, runtime) = connect(TransportKind::Tcp);
+
+    editor.send(&[0xff; 3]).unwrap();
+    let runtime = Connection::<Response, Request>::new(runtime);
+    assert!(matches!(runtime.recv(), Err(TransportError::Malformed(_))));
+}
+
+#[test]
+fn setup_failures_are_not_reported_as_malformed() {
+    let (_listener, address) = TransportListener::bind(TransportKind::Tcp, "127.0.0.1:0").unwrap();
+    let Err(err) = TransportListener::bind(TransportKind::Tcp, &address) else {
+        panic!("bound {} twice", address);
+    };
+    assert!(matches!(err, TransportError::Io(_)));
+    assert!(matches!(
+        HandshakeError::from(err),
+        HandshakeError::Transport(_)
+    ));
+
+    let Err(err) = TransportListener::bind(TransportKind::Replay, "/does/not/exist.roth") else {
+        panic!("opened a recording that doesn't exist");
+    };
+    assert!(matches!(
+        HandshakeError::from(err),
+        HandshakeError::Transport(_)
+    ));
+
+    assert!(matches!(
+        HandshakeError::from(TransportError::Malformed("bad frame".to_string())),
+        HandshakeError::Malformed(_)
+    ));
+}
+
+#[test]
+fn transport_kind_from_str() {
+    assert_eq!("ipc".parse(), Ok(TransportKind::Ipc));
+    assert_eq!("tcp".parse(), Ok(TransportKind::Tcp));
+    assert!("udp".parse::<TransportKind>().is_err());
+    assert_eq!(TransportKind::Tcp.to_string(), "tcp");
+}
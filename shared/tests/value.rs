@@ -0,0 +1,357 @@
+use std::any::TypeId;
+
+use bevy::{
+    core_pipeline::{
+        core_3d::{Camera3dDepthLoadOp, Camera3dDepthTextureUsage, ScreenSpaceTransmissionQuality},
+        tonemapping::{DebandDither, Tonemapping},
+    },
+    math::{Affine3A, Mat3A, Vec3A},
+    pbr::{Cascade, CascadeShadowConfig, CascadeShadowConfigBuilder},
+    prelude::*,
+    reflect::{GetTypeRegistration, TypeRegistry},
+    render::{
+        camera::{CameraRenderGraph, ClearColorConfig, ScalingMode, Viewport},
+        primitives::{Aabb, CubemapFrusta, Frustum},
+        view::{ColorGrading, VisibleEntities},
+    },
+    sprite::Anchor,
+    text::BreakLineOn,
+    utils::Uuid,
+};
+use roth_shared::{ReflectValue, ValueError};
+
+fn registry() -> TypeRegistry {
+    let mut registry = TypeRegistry::default();
+
+    // field types
+    registry.register::<bool>();
+    registry.register::<u8>();
+    registry.register::<u32>();
+    registry.register::<usize>();
+    registry.register::<i32>();
+    registry.register::<f32>();
+    registry.register::<String>();
+    registry.register::<Option<f32>>();
+    registry.register::<Option<String>>();
+    registry.register::<Option<Vec2>>();
+    registry.register::<Option<Rect>>();
+    registry.register::<Uuid>();
+    registry.register::<Vec<f32>>();
+    registry.register::<Vec<Entity>>();
+    registry.register::<Entity>();
+    registry.register::<Vec2>();
+    registry.register::<Rect>();
+    registry.register::<Vec3>();
+    registry.register::<Vec3A>();
+    registry.register::<UVec2>();
+    registry.register::<Quat>();
+    registry.register::<Mat3A>();
+    registry.register::<Affine3A>();
+    registry.register::<Color>();
+    registry.register::<Option<Viewport>>();
+    registry.register::<ScalingMode>();
+    registry.register::<ClearColorConfig>();
+    registry.register::<Camera3dDepthLoadOp>();
+    registry.register::<Camera3dDepthTextureUsage>();
+    registry.register::<ScreenSpaceTransmissionQuality>();
+    registry.register::<CameraRenderGraph>();
+    registry.register::<ColorGrading>();
+    registry.register::<PerspectiveProjection>();
+    registry.register::<OrthographicProjection>();
+    registry.register::<Cascade>();
+    registry.register::<AssetId<Image>>();
+    registry.register::<AssetId<Mesh>>();
+    registry.register::<AssetId<Font>>();
+    registry.register::<Handle<Font>>();
+    registry.register::<Anchor>();
+    registry.register::<Vec<TextSection>>();
+    registry.register::<TextSection>();
+    registry.register::<TextStyle>();
+    registry.register::<JustifyText>();
+    registry.register::<BreakLineOn>();
+
+    // components
+    registry.register::<Name>();
+    registry.register::<Transform>();
+    registry.register::<GlobalTransform>();
+    registry.register::<Visibility>();
+    registry.register::<InheritedVisibility>();
+    registry.register::<ViewVisibility>();
+    registry.register::<Parent>();
+    registry.register::<Children>();
+    registry.register::<Camera>();
+    registry.register::<Camera3d>();
+    registry.register::<Projection>();
+    registry.register::<Tonemapping>();
+    registry.register::<DebandDither>();
+    registry.register::<PointLight>();
+    registry.register::<SpotLight>();
+    registry.register::<DirectionalLight>();
+    registry.register::<CascadeShadowConfig>();
+    registry.register::<Aabb>();
+    registry.register::<Frustum>();
+    registry.register::<CubemapFrusta>();
+    registry.register::<VisibleEntities>();
+    registry.register::<Handle<Image>>();
+    registry.register::<Handle<Mesh>>();
+    registry.register::<Sprite>();
+    registry.register::<Text>();
+    registry.register::<Style>();
+    registry.register::<Node>();
+    registry.register::<BackgroundColor>();
+
+    registry
+}
+
+/// Encodes `value`, decodes it back into `T` and checks that nothing was lost on the way, both
+/// directly and after the encoded value went through a text format like it would over IPC.
+fn assert_round_trip<T: Reflect + FromReflect + GetTypeRegistration>(value: &T) {
+    let registry = registry();
+    let registration = registry
+        .get(TypeId::of::<T>())
+        .unwrap_or_else(|| panic!("{} is not registered", std::any::type_name::<T>()));
+
+    let encoded = ReflectValue::from_reflect(value, &registry).unwrap();
+    let wire: ReflectValue = ron::from_str(&ron::to_string(&encoded).unwrap()).unwrap();
+    assert_eq!(wire, encoded);
+
+    let decoded = wire.to_reflect(registration, &registry).unwrap();
+    let decoded = T::from_reflect(&*decoded)
+        .unwrap_or_else(|| panic!("{:?} is not a {}", decoded, std::any::type_name::<T>()));
+
+    assert_eq!(
+        value.reflect_partial_eq(&decoded),
+        Some(true),
+        "{:?} != {:?}",
+        value as &dyn Reflect,
+        &decoded as &dyn Reflect
+    );
+    assert_eq!(
+        ReflectValue::from_reflect(&decoded, &registry).unwrap(),
+        encoded
+    );
+}
+
+#[test]
+fn name() {
+    assert_round_trip(&Name::new("Player"));
+}
+
+#[test]
+fn transform() {
+    assert_round_trip(
+        &Transform::from_xyz(1.0, -2.5, 3.25)
+            .with_rotation(Quat::from_rotation_y(0.3))
+            .with_scale(Vec3::splat(2.0)),
+    );
+    assert_round_trip(&GlobalTransform::from(Transform::from_xyz(0.1, 0.2, 0.3)));
+}
+
+#[test]
+fn visibility() {
+    assert_round_trip(&Visibility::Hidden);
+    assert_round_trip(&Visibility::Inherited);
+    assert_round_trip(&InheritedVisibility::VISIBLE);
+    assert_round_trip(&ViewVisibility::HIDDEN);
+}
+
+#[test]
+fn hierarchy() {
+    let mut world = World::new();
+    let parent = world.spawn_empty().id();
+    let child = world.spawn_empty().id();
+    world.entity_mut(parent).add_child(child);
+
+    assert_round_trip(world.get::<Parent>(child).unwrap());
+
+    let children = world.get::<Children>(parent).unwrap();
+    let encoded = ReflectValue::from_reflect(children, &registry()).unwrap();
+    assert_eq!(
+        encoded,
+        ReflectValue::Tuple(vec![ReflectValue::Seq(vec![ReflectValue::from_reflect(
+            &child,
+            &registry()
+        )
+        .unwrap()])])
+    );
+}
+
+#[test]
+fn camera() {
+    assert_round_trip(&Camera {
+        order: 2,
+        is_active: false,
+        ..default()
+    });
+    assert_round_trip(&Camera3d::default());
+    assert_round_trip(&Projection::Perspective(PerspectiveProjection {
+        fov: 1.2,
+        ..default()
+    }));
+    assert_round_trip(&Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::FixedVertical(4.0),
+        ..default()
+    }));
+    assert_round_trip(&Tonemapping::AcesFitted);
+    assert_round_trip(&DebandDither::Disabled);
+}
+
+#[test]
+fn lights() {
+    assert_round_trip(&PointLight {
+        color: Color::rgb(1.0, 0.5, 0.25),
+        intensity: 1234.5,
+        shadows_enabled: true,
+        ..default()
+    });
+    assert_round_trip(&SpotLight {
+        inner_angle: 0.1,
+        outer_angle: 0.5,
+        ..default()
+    });
+    assert_round_trip(&DirectionalLight {
+        illuminance: 10_000.0,
+        shadows_enabled: true,
+        ..default()
+    });
+    assert_round_trip(
+        &CascadeShadowConfigBuilder {
+            num_cascades: 2,
+            maximum_distance: 50.0,
+            ..default()
+        }
+        .build(),
+    );
+}
+
+#[test]
+fn bounds() {
+    assert_round_trip(&Aabb::from_min_max(Vec3::new(-1.0, -2.0, -3.0), Vec3::ONE));
+    assert_round_trip(&Frustum::default());
+    assert_round_trip(&CubemapFrusta::default());
+    assert_round_trip(&VisibleEntities::default());
+}
+
+#[test]
+fn handles() {
+    assert_round_trip(&Handle::<Image>::default());
+    assert_round_trip(&Handle::<Mesh>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(0x1234_5678),
+    }));
+
+    // a strong handle keeps its asset alive, there's nothing to decode one from
+    let mut meshes = Assets::<Mesh>::default();
+    let strong = meshes.add(Cuboid::default());
+    assert!(ReflectValue::from_reflect(&strong, &registry()).is_err());
+}
+
+#[test]
+fn sprite() {
+    assert_round_trip(&Sprite {
+        color: Color::rgba(1.0, 0.5, 0.25, 0.5),
+        flip_x: true,
+        custom_size: Some(Vec2::new(32.0, 16.0)),
+        rect: Some(Rect::new(0.0, 0.0, 8.0, 8.0)),
+        anchor: Anchor::TopLeft,
+        ..default()
+    });
+    assert_round_trip(&Sprite {
+        anchor: Anchor::Custom(Vec2::new(0.25, -0.5)),
+        ..default()
+    });
+}
+
+#[test]
+fn text() {
+    assert_round_trip(
+        &Text::from_sections([
+            TextSection::new(
+                "Score: ",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            TextSection::from_style(TextStyle::default()),
+        ])
+        .with_justify(JustifyText::Center)
+        .with_no_wrap(),
+    );
+}
+
+#[test]
+fn ui() {
+    assert_round_trip(&Style {
+        display: Display::Grid,
+        position_type: PositionType::Absolute,
+        left: Val::Px(10.0),
+        width: Val::Percent(50.0),
+        height: Val::Vw(20.0),
+        margin: UiRect::all(Val::Auto),
+        padding: UiRect::horizontal(Val::Px(4.0)),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::SpaceBetween,
+        aspect_ratio: Some(1.5),
+        grid_template_columns: RepeatedGridTrack::flex(3, 1.0),
+        ..default()
+    });
+    assert_round_trip(&Node::default());
+    assert_round_trip(&BackgroundColor(Color::BLUE));
+}
+
+#[test]
+fn field_lookup() {
+    let registry = registry();
+
+    let value = ReflectValue::from_reflect(&Name::new("Player"), &registry).unwrap();
+    assert_eq!(value.as_str(), Some("Player"));
+
+    let value = ReflectValue::from_reflect(&Transform::from_xyz(1.0, 2.0, 3.0), &registry).unwrap();
+    assert_eq!(
+        value.field("translation"),
+        Some(&ReflectValue::from_reflect(&Vec3::new(1.0, 2.0, 3.0), &registry).unwrap())
+    );
+    assert_eq!(value.field("position"), None);
+}
+
+#[test]
+fn path_lookup() {
+    let registry = registry();
+
+    let mut value =
+        ReflectValue::from_reflect(&Transform::from_xyz(1.0, 2.0, 3.0), &registry).unwrap();
+    assert_eq!(
+        value.path_mut("translation.y"),
+        Some(&mut ReflectValue::F32(2.0))
+    );
+    *value.path_mut(".scale.x").unwrap() = ReflectValue::F32(4.0);
+    assert_eq!(
+        value.field("scale"),
+        Some(&ReflectValue::from_reflect(&Vec3::new(4.0, 1.0, 1.0), &registry).unwrap())
+    );
+    assert_eq!(value.path_mut("translation.w"), None);
+    assert_eq!(value.path_mut("translation..x"), None);
+
+    let mut value =
+        ReflectValue::from_reflect(&GlobalTransform::from_xyz(1.0, 2.0, 3.0), &registry).unwrap();
+    assert_eq!(
+        value.path_mut("0.translation.z"),
+        Some(&mut ReflectValue::F32(3.0))
+    );
+
+    let mut value = ReflectValue::Tuple(vec![ReflectValue::U8(1), ReflectValue::U8(2)]);
+    assert_eq!(value.path_mut("[1]"), Some(&mut ReflectValue::U8(2)));
+    assert_eq!(value.path_mut(".1"), Some(&mut ReflectValue::U8(2)));
+    assert_eq!(value.path_mut("[2]"), None);
+}
+
+#[test]
+fn mismatched_value_is_an_error() {
+    let registry = registry();
+    let registration = registry.get(TypeId::of::<Transform>()).unwrap();
+
+    let value = ReflectValue::from_reflect(&Name::new("Player"), &registry).unwrap();
+    let result: Result<_, ValueError> = value.to_reflect(registration, &registry);
+    assert!(result.is_err());
+}
@@ -1,12 +1,51 @@
-use crate::{drawer::Drawer, inspector::Inspector, scene_viewer::SceneViewer, RootContext};
+use crate::{
+    console::{LogLine, MAX_LOG_LINES},
+    crash_report::{CrashReport, CrashReportView, StderrTail},
+    diagnostics::DiagnosticHistory,
+    drawer::Drawer,
+    history::{self, History, HistoryAction, UndoShortcuts},
+    inspector::Inspector,
+    notifications::{Notifications, Toasts},
+    runtime_client::{RequestError, RuntimeClient},
+    scene_viewer::SceneViewer,
+    world_model::WorldModel,
+    RootContext,
+};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    future::Future,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
 use bevy::ecs::entity::Entity;
 use dioxus::prelude::*;
 use futures_util::stream::StreamExt;
-use ipc_channel::ipc::{IpcOneShotServer, IpcReceiver, IpcSender};
-use roth_shared::{EditorToRuntimeMsg, RuntimeToEditorMsg};
+use roth_shared::{
+    handshake::{Capabilities, HandshakeError, HandshakeReply, Hello},
+    launch::LaunchArgs,
+    recording::{Recorder, RecordingTransport, Side},
+    transport::{self, Connection, TransportError, TransportKind, TransportListener},
+    ComponentData, EditorToRuntimeMsg, LoadMode, PlayState, Request, Response, RuntimeToEditorMsg,
+    TypeRegistrySchema, HEARTBEAT_INTERVAL,
+};
 use tokio::sync::broadcast;
 use tpaint::{components::image::Image, prelude::*};
 
+/// Scene the editor saves to until another one is picked with Save As, relative to the asset folder.
+pub const SCENE_PATH: &str = "scenes/main.scn.ron";
+
+/// A runtime that sends heartbeats counts as unresponsive after missing this many of them.
+const MISSED_HEARTBEATS: u32 = 5;
+/// A runtime that crashes sooner than this after starting isn't restarted automatically, it would
+/// most likely crash again.
+const MIN_UPTIME_FOR_RESTART: Duration = Duration::from_secs(10);
+/// How long the runtime has to connect after starting, generous because `cargo run` builds the game
+/// first.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum RuntimeStatus {
     Stopped,
@@ -16,50 +55,215 @@ pub enum RuntimeStatus {
 pub struct SharedState {
     pub project_path: String,
     pub runtime_status: RuntimeStatus,
-    runtime_sender: Option<tokio::sync::mpsc::UnboundedSender<EditorToRuntimeMsg>>,
-    runtime_receiver: Option<tokio::sync::mpsc::UnboundedReceiver<EditorToRuntimeMsg>>,
-    pub runtime_output: Option<tokio::sync::mpsc::UnboundedReceiver<String>>,
-    pub runtime_response: tokio::sync::broadcast::Sender<RuntimeToEditorMsg>,
+    /// whether the running runtime is being edited or plays the game
+    pub play_state: PlayState,
+    runtime: Option<RuntimeClient>,
+    runtime_receiver: Option<tokio::sync::mpsc::UnboundedReceiver<Request>>,
+    /// what the runtime logged, shown in the console
+    pub runtime_logs: VecDeque<LogLine>,
+    /// recent values of the runtime's diagnostics by path, shown in the diagnostics tab
+    pub diagnostics: BTreeMap<String, DiagnosticHistory>,
+    pub runtime_response: tokio::sync::broadcast::Sender<Response>,
     pub selected_entity: Option<Entity>,
+    /// entities of the running runtime
+    pub world: WorldModel,
+    /// what the running runtime said it supports when it connected
+    pub runtime_capabilities: Capabilities,
+    /// types the running runtime has registered, `None` until it answered `GetTypeRegistry`
+    pub type_registry: Option<Arc<TypeRegistrySchema>>,
+    /// resources of the running runtime, refreshed with `GetResources`
+    pub resources: Vec<ComponentData>,
+    /// how the runtime connects to the editor
+    pub transport: TransportKind,
+    /// what the tcp transport listens on, use port 0 to pick any free port, or the recording to replay
+    pub transport_address: String,
+    /// where to record the session with the runtime to, if anywhere
+    pub record_path: Option<String>,
+    /// set when the runtime could not be attached, shown in place of the viewport
+    pub runtime_error: Option<String>,
+    /// set when the runtime exited with an error without being asked to, shown in place of the viewport
+    pub crash_report: Option<CrashReport>,
+    /// set while the runtime hasn't sent a heartbeat in a while
+    pub runtime_unresponsive: bool,
+    /// start the runtime again when it crashes, reloading the last saved scene
+    pub auto_restart: bool,
+    /// asset path of the scene that was saved last
+    pub last_saved_scene: Option<String>,
+    /// asset path Save writes the scene to, changed by Save As
+    pub scene_path: String,
+    /// what the runtime answered to the last save or scene load, shown in the top bar
+    pub scene_status: Option<Result<String, String>>,
+    /// failed requests, shown as toasts and kept in the drawer
+    pub notifications: Notifications,
+    /// edits that can be undone, forgotten when the runtime goes away
+    pub history: History,
 }
 
 impl SharedState {
     pub fn start_runtime(&mut self) {
-        let (runtime_sender, runtime_receiver) =
-            tokio::sync::mpsc::unbounded_channel::<EditorToRuntimeMsg>();
-        self.runtime_sender = Some(runtime_sender);
+        let (runtime, runtime_receiver) = RuntimeClient::new();
+        self.runtime = Some(runtime);
         self.runtime_receiver = Some(runtime_receiver);
         self.runtime_status = RuntimeStatus::Running;
+        self.runtime_error = None;
+        self.crash_report = None;
+        self.runtime_unresponsive = false;
+        self.diagnostics.clear();
+    }
+
+    pub fn push_log(&mut self, line: LogLine) {
+        if self.runtime_logs.len() >= MAX_LOG_LINES {
+            self.runtime_logs.pop_front();
+        }
+        self.runtime_logs.push_back(line);
     }
 
     pub fn stop_runtime(&mut self) {
-        let sender = self.runtime_sender.take();
-        if let Some(sender) = sender {
-            sender.send(EditorToRuntimeMsg::Shutdown).unwrap();
+        if let Some(runtime) = self.runtime.take() {
+            runtime.send(EditorToRuntimeMsg::Shutdown);
         }
+        self.clear_runtime();
+    }
+
+    /// Called when the runtime process exited without being stopped, `crash_report` is set when it
+    /// didn't exit successfully.
+    pub fn runtime_exited(&mut self, crash_report: Option<CrashReport>) {
+        self.runtime = None;
+        self.crash_report = crash_report;
+        self.clear_runtime();
+    }
+
+    fn clear_runtime(&mut self) {
+        self.runtime_status = RuntimeStatus::Stopped;
+        self.play_state = PlayState::Editing;
+        self.runtime_unresponsive = false;
+        self.world.clear();
+        self.history.clear();
+        self.runtime_capabilities = Capabilities::NONE;
+        self.type_registry = None;
+        self.resources.clear();
+    }
+
+    pub fn save_scene(&mut self) {
+        self.save_scene_as(self.scene_path.clone());
+    }
+
+    /// Saves to `path` in the asset folder, which becomes the scene path once the runtime saved it.
+    pub fn save_scene_as(&mut self, path: String) {
+        self.scene_status = None;
+        self.send_to_runtime(EditorToRuntimeMsg::Save { path });
+    }
+
+    pub fn load_scene(&mut self, path: String, mode: LoadMode) {
+        self.scene_status = None;
+        self.send_to_runtime(EditorToRuntimeMsg::LoadScene { path, mode });
+    }
+
+    /// Folder the runtime resolves scene paths against.
+    pub fn asset_root(&self) -> PathBuf {
+        Path::new(&self.project_path).join("assets")
+    }
+
+    /// Called when the handshake with a freshly started runtime fails.
+    pub fn refuse_runtime(&mut self, err: HandshakeError) {
+        log::error!("refusing runtime: {}", err);
+        self.runtime = None;
         self.runtime_status = RuntimeStatus::Stopped;
+        self.runtime_error = Some(err.to_string());
     }
 
     pub fn send_to_runtime(&self, msg: EditorToRuntimeMsg) {
-        if let Some(runtime_sender) = &self.runtime_sender {
-            runtime_sender.send(msg).unwrap();
+        if let Some(runtime) = &self.runtime {
+            runtime.send(msg);
+        }
+    }
+
+    /// Sends an edit to the runtime that can be undone, resolves with the runtime's answer.
+    pub fn edit(
+        &self,
+        msg: EditorToRuntimeMsg,
+    ) -> impl Future<Output = Result<RuntimeToEditorMsg, RequestError>> + Send + 'static {
+        history::edit(
+            &self.history,
+            self.runtime(),
+            &self.world,
+            &self.resources,
+            msg,
+        )
+    }
+
+    /// Like [`SharedState::send_to_runtime`] for edits, see [`SharedState::edit`].
+    pub fn send_edit(&self, msg: EditorToRuntimeMsg) {
+        tokio::spawn(self.edit(msg));
+    }
+
+    pub fn undo(&self) {
+        if let Some(runtime) = self.runtime() {
+            tokio::spawn(self.history.clone().undo(runtime));
         }
     }
+
+    pub fn redo(&self) {
+        if let Some(runtime) = self.runtime() {
+            tokio::spawn(self.history.clone().redo(runtime));
+        }
+    }
+
+    /// Client for the running runtime, clone it out before awaiting a [`RuntimeClient::request`].
+    pub fn runtime(&self) -> Option<RuntimeClient> {
+        self.runtime.clone()
+    }
 }
 
 pub fn app(cx: Scope) -> Element {
     use_shared_state_provider(cx, || SharedState {
         project_path: "/home/dylan/dev/roth/example_bevy".to_string(),
         runtime_status: RuntimeStatus::Stopped,
-        runtime_sender: None,
+        play_state: PlayState::Editing,
+        runtime: None,
         runtime_receiver: None,
-        runtime_output: None,
-        runtime_response: broadcast::channel::<RuntimeToEditorMsg>(16).0,
+        runtime_logs: VecDeque::new(),
+        diagnostics: BTreeMap::new(),
+        runtime_response: broadcast::channel::<Response>(16).0,
         selected_entity: None,
+        world: WorldModel::default(),
+        runtime_capabilities: Capabilities::NONE,
+        type_registry: None,
+        resources: vec![],
+        // `ROTH_REPLAY` plays back a recording instead of starting the runtime
+        transport: match std::env::var("ROTH_REPLAY") {
+            Ok(_) => TransportKind::Replay,
+            Err(_) => TransportKind::default(),
+        },
+        transport_address: std::env::var("ROTH_REPLAY").unwrap_or("127.0.0.1:0".to_string()),
+        record_path: std::env::var("ROTH_RECORD").ok(),
+        runtime_error: None,
+        crash_report: None,
+        runtime_unresponsive: false,
+        auto_restart: std::env::var("ROTH_AUTO_RESTART").is_ok(),
+        last_saved_scene: None,
+        scene_path: SCENE_PATH.to_string(),
+        scene_status: None,
+        notifications: Notifications::default(),
+        history: History::default(),
     });
     let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let undo_shortcuts = use_ref(cx, UndoShortcuts::default);
 
     let runtime_status = shared_state.read().runtime_status.clone();
+    let play_state = shared_state.read().play_state;
+    let runtime_error = shared_state.read().runtime_error.clone();
+    let has_crash_report = shared_state.read().crash_report.is_some();
+    let runtime_unresponsive = shared_state.read().runtime_unresponsive;
+    let auto_restart = shared_state.read().auto_restart;
+    let scene_status = shared_state
+        .read()
+        .scene_status
+        .clone()
+        .map(|status| match status {
+            Ok(message) => ("text-zinc-400", message),
+            Err(message) => ("text-red-400", message),
+        });
 
     use_on_create(cx, move || {
         to_owned![shared_state];
@@ -71,6 +275,16 @@ pub fn app(cx: Scope) -> Element {
     render! {
         view {
             class: "w-full h-full p-5 bg-zinc-700 flex-col gap-y-8",
+            onkeydown: move |event| {
+                match undo_shortcuts.write().key(event.physical_key, event.element_state) {
+                    Some(HistoryAction::Undo) => shared_state.read().undo(),
+                    Some(HistoryAction::Redo) => shared_state.read().redo(),
+                    None => {}
+                }
+            },
+            onkeyup: move |event| {
+                undo_shortcuts.write().key(event.physical_key, event.element_state);
+            },
 
             view {
                 class: "rounded-5 w-full py-5 px-15 bg-zinc-800 text-white justify-between items-center",
@@ -91,18 +305,80 @@ pub fn app(cx: Scope) -> Element {
                 }
 
                 view {
-                    class: "gap-x-16",
+                    class: "gap-x-16 items-center",
+
+                    if let Some((class, message)) = scene_status {
+                        rsx! {
+                            view {
+                                class: "{class} text-14",
+                                "{message}"
+                            }
+                        }
+                    }
+
+                    view {
+                        class: "text-white text-18",
+                        tabindex: 0,
+                        onclick: move |_| {
+                            shared_state.read().undo();
+                        },
+
+                        "Undo"
+                    }
 
                     view {
                         class: "text-white text-18",
                         tabindex: 0,
                         onclick: move |_| {
-                            shared_state.read().send_to_runtime(EditorToRuntimeMsg::Save);
+                            shared_state.read().redo();
+                        },
+
+                        "Redo"
+                    }
+
+                    view {
+                        class: "text-white text-18",
+                        tabindex: 0,
+                        onclick: move |_| {
+                            shared_state.write().save_scene();
                         },
 
                         "Save"
                     }
 
+                    view {
+                        class: "text-white text-18",
+                        tabindex: 0,
+                        onclick: move |_| {
+                            to_owned![shared_state];
+                            cx.spawn(async move {
+                                let (asset_root, scene_path) = {
+                                    let shared_state = shared_state.read();
+                                    (shared_state.asset_root(), shared_state.scene_path.clone())
+                                };
+                                match pick_scene_path(asset_root, &scene_path).await {
+                                    Some(Ok(path)) => shared_state.write().save_scene_as(path),
+                                    Some(Err(err)) => shared_state.write().scene_status = Some(Err(err)),
+                                    None => {}
+                                }
+                            });
+                        },
+
+                        "Save As"
+                    }
+
+                    view {
+                        class: "text-white text-18",
+                        tabindex: 0,
+                        onclick: move |_| {
+                            let mut shared_state = shared_state.write();
+                            shared_state.auto_restart = !shared_state.auto_restart;
+                        },
+
+                        if auto_restart { rsx! { "Auto restart: on" } } else { rsx! { "Auto restart: off" } }
+                    }
+
+                    // playing happens inside the running app, the process only needs a restart when the game's code changed
                     view {
                         class: "text-white text-18",
                         tabindex: 0,
@@ -114,7 +390,43 @@ pub fn app(cx: Scope) -> Element {
                             }
                         },
 
-                        if runtime_status == RuntimeStatus::Stopped { rsx! { "Play" } } else { rsx! { "Stop" } }
+                        if runtime_status == RuntimeStatus::Stopped { rsx! { "Start" } } else { rsx! { "Quit" } }
+                    }
+
+                    if runtime_status != RuntimeStatus::Stopped {
+                        rsx! {
+                            view {
+                                class: "text-white text-18",
+                                tabindex: 0,
+                                onclick: move |_| {
+                                    let msg = match play_state {
+                                        PlayState::Playing => EditorToRuntimeMsg::Pause,
+                                        PlayState::Editing | PlayState::Paused => EditorToRuntimeMsg::Play,
+                                    };
+                                    shared_state.read().send_to_runtime(msg);
+                                },
+
+                                match play_state {
+                                    PlayState::Editing => rsx! { "Play" },
+                                    PlayState::Playing => rsx! { "Pause" },
+                                    PlayState::Paused => rsx! { "Resume" },
+                                }
+                            }
+
+                            if play_state != PlayState::Editing {
+                                rsx! {
+                                    view {
+                                        class: "text-white text-18",
+                                        tabindex: 0,
+                                        onclick: move |_| {
+                                            shared_state.read().send_to_runtime(EditorToRuntimeMsg::Stop);
+                                        },
+
+                                        "Stop"
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -131,10 +443,31 @@ pub fn app(cx: Scope) -> Element {
                 view {
                     class: "w-65% flex-col h-full bg-transparent rounded-5",
 
+                    Toasts {}
+
                     if runtime_status != RuntimeStatus::Stopped {
                         rsx! {
+                            if runtime_unresponsive {
+                                rsx! {
+                                    view {
+                                        class: "w-full bg-zinc-900 p-8 text-yellow-400 text-14",
+                                        "The runtime stopped responding"
+                                    }
+                                }
+                            }
                             RuntimeWindow {}
                         }
+                    } else if has_crash_report {
+                        rsx! {
+                            CrashReportView {}
+                        }
+                    } else if let Some(runtime_error) = runtime_error {
+                        rsx! {
+                            view {
+                                class: "w-full h-full justify-center items-center bg-zinc-900 p-20 text-red-400 text-14",
+                                "{runtime_error}"
+                            }
+                        }
                     } else {
                         rsx! {
                             view {
@@ -152,105 +485,383 @@ pub fn app(cx: Scope) -> Element {
     }
 }
 
+async fn kill_runtime(runtime_process: &mut Option<tokio::process::Child>) {
+    if let Some(runtime_process) = runtime_process {
+        let _ = runtime_process.kill().await;
+    }
+}
+
+/// `path` as an asset path, `None` when it's outside of `asset_root`.
+pub fn asset_path(asset_root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(asset_root).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|it| it.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// Asks where to save the scene, answers with the path relative to `asset_root` or `None` when the
+/// dialog was cancelled.
+async fn pick_scene_path(asset_root: PathBuf, scene_path: &str) -> Option<Result<String, String>> {
+    let current = asset_root.join(scene_path);
+    let mut dialog = rfd::AsyncFileDialog::new()
+        .set_title("Save scene as")
+        .add_filter("Scene", &["ron"]);
+    if let Some(directory) = current.parent() {
+        dialog = dialog.set_directory(directory);
+    }
+    if let Some(file_name) = current.file_name() {
+        dialog = dialog.set_file_name(file_name.to_string_lossy());
+    }
+    let file = dialog.save_file().await?;
+
+    // the runtime only saves inside its asset folder
+    Some(asset_path(&asset_root, file.path()).ok_or_else(|| {
+        format!(
+            "{} is outside of the asset folder {}",
+            file.path().display(),
+            asset_root.display()
+        )
+    }))
+}
+
+/// Resolves when the runtime exits, never when there is no runtime process.
+async fn wait_for_runtime(
+    runtime_process: &mut Option<tokio::process::Child>,
+) -> std::io::Result<ExitStatus> {
+    match runtime_process {
+        Some(runtime_process) => runtime_process.wait().await,
+        None => std::future::pending().await,
+    }
+}
+
 fn RuntimeWindow<'a>(cx: Scope<'a>) -> Element {
-    let window_id = cx
-        .consume_context::<RootContext>()
-        .unwrap()
-        .window_id
-        .to_string();
+    let window_id = cx.consume_context::<RootContext>().unwrap().window_id;
     let shared_state = use_shared_state::<SharedState>(cx).unwrap();
 
     let runtime_sender = use_coroutine(cx, |mut rx: UnboundedReceiver<EditorToRuntimeMsg>| {
         to_owned![shared_state];
         async move {
-            let (server, server_name) =
-                IpcOneShotServer::<(IpcSender<EditorToRuntimeMsg>, String)>::new().unwrap();
-
-            let project_path = shared_state.read().project_path.clone();
-            let mut runtime_process = tokio::process::Command::new("cargo")
-                .arg("run")
-                .arg("--manifest-path")
-                .arg(format!("{}/Cargo.toml", project_path))
-                .arg("--")
-                .arg("--window-id")
-                .arg(window_id)
-                .arg("--ipc-server")
-                .arg(server_name)
-                // .stderr(Stdio::piped())
-                // .stdout(Stdio::piped())
-                .spawn()
-                .expect("failed to start runtime process");
-
-            // let stdout = runtime_process
-            //     .stdout
-            //     .take()
-            //     .expect("runtime_process did not have a handle to stdout");
-            // let stderr = runtime_process
-            //     .stderr
-            //     .take()
-            //     .expect("runtime_process did not have a handle to stderr");
-            // let mut stdout_reader = BufReader::new(stdout).lines();
-            // let mut stderr_reader = BufReader::new(stderr).lines();
-
-            // let (console_output_sender, console_output_receiver) =
-            //     tokio::sync::mpsc::unbounded_channel::<String>();
-            // shared_state.write().runtime_output = Some(console_output_receiver);
-            // tokio::spawn({
-            //     let runtime_output = console_output_sender.clone();
-            //     async move {
-            //         loop {
-            //             tokio::select! {
-            //                 Ok(Some(line)) = stdout_reader.next_line() => {
-            //                     runtime_output.send(line).unwrap();
-            //                 }
-
-            //                 Ok(Some(line)) = stderr_reader.next_line() => {
-            //                     // println!("{}", line);
-            //                     runtime_output.send(line).unwrap();
-            //                 }
-            //             }
-            //         }
-            //     }
-            // });
-
-            let (_, (runtime_sender, oneshot_server_name)) =
-                tokio::task::spawn_blocking(move || server.accept().unwrap())
-                    .await
-                    .unwrap();
-
-            let (tx1, runtime_receiver): (
-                IpcSender<RuntimeToEditorMsg>,
-                IpcReceiver<RuntimeToEditorMsg>,
-            ) = ipc_channel::ipc::channel().unwrap();
-
-            let tx: IpcSender<IpcSender<RuntimeToEditorMsg>> =
-                IpcSender::connect(oneshot_server_name).unwrap();
-            tx.send(tx1).unwrap();
-
-            let mut runtime_message_stream = runtime_receiver.to_stream();
-
-            let mut app_runtime_receiver = {
-                let mut shared_state = shared_state.write();
-                shared_state
-                    .runtime_receiver
-                    .take()
-                    .expect("Runtime component was created without any runtime receiver")
-            };
-
+            let mut reload_scene = None;
+            // runs once per runtime process, again when it's restarted after a crash
             loop {
-                tokio::select! {
-                    Some(msg) = rx.next() => {
-                        runtime_sender.send(msg).unwrap();
+                let (transport_kind, transport_address) = {
+                    let shared_state = shared_state.read();
+                    (
+                        shared_state.transport,
+                        shared_state.transport_address.clone(),
+                    )
+                };
+                let (listener, editor_address) =
+                    match TransportListener::bind(transport_kind, &transport_address) {
+                        Ok(listener) => listener,
+                        Err(err) => {
+                            shared_state.write().refuse_runtime(err.into());
+                            return;
+                        }
+                    };
+
+                // a replay stands in for the runtime, so there is nothing to start
+                let mut runtime_process = (transport_kind != TransportKind::Replay).then(|| {
+                    let project_path = shared_state.read().project_path.clone();
+                    let mut launch = LaunchArgs::new(transport_kind, editor_address.clone());
+                    launch.window_id = Some(window_id);
+                    tokio::process::Command::new("cargo")
+                        .arg("run")
+                        .arg("--manifest-path")
+                        .arg(format!("{}/Cargo.toml", project_path))
+                        .arg("--")
+                        .args(launch.to_args())
+                        .stderr(Stdio::piped())
+                        .spawn()
+                        .expect("failed to start runtime process")
+                });
+                let started = Instant::now();
+
+                let stderr_tail = StderrTail::default();
+                let mut stderr_reader = runtime_process
+                    .as_mut()
+                    .and_then(|it| it.stderr.take())
+                    .map(|stderr| tokio::spawn(stderr_tail.clone().capture(stderr)));
+
+                let hello = Hello::editor(
+                    Capabilities::INPUT
+                        | Capabilities::ENTITIES
+                        | Capabilities::ENTITY_DIFFS
+                        | Capabilities::SCENES
+                        | Capabilities::TYPE_REGISTRY
+                        | Capabilities::RESOURCES
+                        | Capabilities::LOGS
+                        | Capabilities::DIAGNOSTICS
+                        | Capabilities::HEARTBEAT,
+                );
+                let record_path = shared_state.read().record_path.clone();
+                let mut accept = tokio::task::spawn_blocking(move || {
+                    let mut transport = listener.accept()?;
+                    if let Some(path) = record_path {
+                        match Recorder::create(&path) {
+                            Ok(recorder) => {
+                                transport = Arc::new(RecordingTransport::new(
+                                    transport,
+                                    Arc::new(recorder),
+                                    Side::Editor,
+                                ));
+                            }
+                            Err(err) => log::error!("not recording to {}: {}", path, err),
+                        }
                     }
-                    Some(msg) = app_runtime_receiver.recv() => {
-                        runtime_sender.send(msg).unwrap();
+                    let runtime_hello = transport::recv_message::<Hello>(&*transport)?;
+                    Ok::<_, TransportError>((transport, runtime_hello))
+                });
+                // a runtime that never connects would leave the accept blocking forever
+                let connected = tokio::select! {
+                    handshake = &mut accept => Ok(handshake.unwrap()),
+                    exit = wait_for_runtime(&mut runtime_process) => Err(match exit {
+                        Ok(status) => format!("{}, before connecting to the editor", status),
+                        Err(err) => format!("lost track of the runtime process: {}", err),
+                    }),
+                    _ = tokio::time::sleep(CONNECT_TIMEOUT) => {
+                        kill_runtime(&mut runtime_process).await;
+                        Err(format!(
+                            "didn't connect to the editor within {} seconds",
+                            CONNECT_TIMEOUT.as_secs()
+                        ))
                     }
-                    Some(msg) = runtime_message_stream.next() => {
-                        let msg = msg.unwrap();
-                        shared_state.read().runtime_response.send(msg).unwrap();
+                };
+                let handshake = match connected {
+                    Ok(handshake) => handshake,
+                    Err(status) => {
+                        // connecting ourselves lets the blocked accept finish
+                        tokio::task::spawn_blocking(move || {
+                            let _ = transport::connect(transport_kind, &editor_address);
+                        });
+                        if let Some(stderr_reader) = stderr_reader.take() {
+                            let _ =
+                                tokio::time::timeout(Duration::from_secs(1), stderr_reader).await;
+                        }
+                        shared_state
+                            .write()
+                            .runtime_exited(Some(CrashReport::new(status, stderr_tail.lines())));
+                        return;
+                    }
+                };
+                let (transport, runtime_hello) = match handshake {
+                    Ok(handshake) => handshake,
+                    Err(err) => {
+                        kill_runtime(&mut runtime_process).await;
+                        shared_state.write().refuse_runtime(err.into());
+                        return;
+                    }
+                };
+
+                if let Err(err) = hello.check_compatible(&runtime_hello) {
+                    let _ =
+                        transport::send_message::<HandshakeReply>(&*transport, &Err(err.clone()));
+                    kill_runtime(&mut runtime_process).await;
+                    shared_state.write().refuse_runtime(err);
+                    return;
+                }
+                transport::send_message::<HandshakeReply>(&*transport, &Ok(hello)).unwrap();
+
+                let connection = Connection::<Request, Response>::new(transport);
+                let mut runtime_message_stream = {
+                    // the transport blocks, so responses are read on their own thread
+                    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+                    let connection = connection.clone();
+                    std::thread::spawn(move || {
+                        while let Ok(response) = connection.recv() {
+                            if sender.send(response).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    receiver
+                };
+
+                let (client, mut app_runtime_receiver) = {
+                    let mut shared_state = shared_state.write();
+                    let client = shared_state
+                        .runtime()
+                        .expect("Runtime component was created without any runtime client");
+                    let receiver = shared_state
+                        .runtime_receiver
+                        .take()
+                        .expect("Runtime component was created without any runtime receiver");
+                    (client, receiver)
+                };
+
+                let capabilities = runtime_hello.capabilities;
+                shared_state.write().runtime_capabilities = capabilities;
+                // the answer seeds the world model, after that the runtime only sends diffs
+                if capabilities.contains(Capabilities::ENTITIES) {
+                    client.send(EditorToRuntimeMsg::GetEntities);
+                }
+                if capabilities.contains(Capabilities::TYPE_REGISTRY) {
+                    client.send(EditorToRuntimeMsg::GetTypeRegistry);
+                }
+                if let Some(path) = reload_scene.take() {
+                    if capabilities.contains(Capabilities::SCENES) {
+                        client.send(EditorToRuntimeMsg::LoadScene {
+                            path,
+                            mode: LoadMode::Replace,
+                        });
                     }
-                    _ = runtime_process.wait() => break,
                 }
+
+                let sends_heartbeats = capabilities.contains(Capabilities::HEARTBEAT);
+                let mut last_seen = Instant::now();
+                let mut unresponsive = false;
+                let mut disconnected = false;
+
+                let exit = loop {
+                    tokio::select! {
+                        Some(msg) = rx.next() => {
+                            client.send(msg);
+                        }
+                        Some(request) = app_runtime_receiver.recv() => {
+                            if !disconnected {
+                                if let Err(err) = connection.send(&request) {
+                                    log::error!("lost the connection to the runtime: {}", err);
+                                    disconnected = true;
+                                    shared_state.write().runtime_unresponsive = true;
+                                }
+                            }
+                            if disconnected {
+                                // nothing is going to answer, fail what's waiting instead of hanging
+                                client.disconnect();
+                            }
+                        }
+                        _ = tokio::time::sleep_until((last_seen + HEARTBEAT_INTERVAL * MISSED_HEARTBEATS).into()), if sends_heartbeats && !unresponsive => {
+                            unresponsive = true;
+                            shared_state.write().runtime_unresponsive = true;
+                        }
+                        Some(response) = runtime_message_stream.recv() => {
+                            last_seen = Instant::now();
+                            if unresponsive {
+                                unresponsive = false;
+                                shared_state.write().runtime_unresponsive = false;
+                            }
+                            client.resolve(&response);
+                            match &response.msg {
+                                RuntimeToEditorMsg::Entities { .. } | RuntimeToEditorMsg::EntitiesChanged { .. } => {
+                                    shared_state.write().world.apply(&response.msg);
+                                }
+                                RuntimeToEditorMsg::TypeRegistry { schema } => {
+                                    shared_state.write().type_registry = Some(Arc::new(schema.clone()));
+                                }
+                                RuntimeToEditorMsg::Resources { resources } => {
+                                    shared_state.write().resources = resources.clone();
+                                }
+                                RuntimeToEditorMsg::ResourceSet { resource } => {
+                                    let mut shared_state = shared_state.write();
+                                    let resources = &mut shared_state.resources;
+                                    match resources.iter_mut().find(|it| it.type_name == resource.type_name) {
+                                        Some(existing) => *existing = resource.clone(),
+                                        None => resources.push(resource.clone()),
+                                    }
+                                }
+                                RuntimeToEditorMsg::Log { level, target, message, fields, timestamp } => {
+                                    shared_state.write().push_log(LogLine {
+                                        level: *level,
+                                        target: target.clone(),
+                                        message: message.clone(),
+                                        fields: fields.clone(),
+                                        timestamp: *timestamp,
+                                    });
+                                }
+                                RuntimeToEditorMsg::Saved { path, bytes } => {
+                                    let mut shared_state = shared_state.write();
+                                    shared_state.scene_path = path.clone();
+                                    shared_state.last_saved_scene = Some(path.clone());
+                                    shared_state.scene_status = Some(Ok(format!("Saved {} ({} bytes)", path, bytes)));
+                                }
+                                RuntimeToEditorMsg::SaveFailed { path, error } => {
+                                    let mut shared_state = shared_state.write();
+                                    shared_state.scene_status = Some(Err(format!("Saving {} failed: {}", path, error)));
+                                    shared_state.notifications.push("save failed", format!("{}: {}", path, error));
+                                }
+                                RuntimeToEditorMsg::SceneLoading { path, stage } => {
+                                    shared_state.write().scene_status = Some(Ok(format!("Loading {} ({})", path, stage.as_str())));
+                                }
+                                RuntimeToEditorMsg::SceneLoaded { path, mode, entities } => {
+                                    let mut shared_state = shared_state.write();
+                                    if *mode == LoadMode::Replace {
+                                        shared_state.scene_path = path.clone();
+                                        shared_state.selected_entity = None;
+                                        // the entities the edits were made to are gone
+                                        shared_state.history.clear();
+                                    }
+                                    shared_state.scene_status = Some(Ok(format!("Loaded {} ({} entities)", path, entities)));
+                                    // the scene viewer starts over from a fresh snapshot
+                                    client.send(EditorToRuntimeMsg::GetEntities);
+                                }
+                                RuntimeToEditorMsg::SceneLoadFailed { path, error } => {
+                                    let mut shared_state = shared_state.write();
+                                    shared_state.scene_status = Some(Err(format!("Loading {} failed: {}", path, error)));
+                                    shared_state.notifications.push("load failed", format!("{}: {}", path, error));
+                                }
+                                RuntimeToEditorMsg::PlayStateChanged { state } => {
+                                    let mut shared_state = shared_state.write();
+                                    match state {
+                                        PlayState::Editing => shared_state.history.stop_playing(),
+                                        PlayState::Playing | PlayState::Paused => shared_state.history.start_playing(),
+                                    }
+                                    shared_state.play_state = *state;
+                                }
+                                RuntimeToEditorMsg::Error { kind, message, .. } => {
+                                    shared_state.write().notifications.push(kind.as_str(), message.clone());
+                                }
+                                RuntimeToEditorMsg::Diagnostics { diagnostics } => {
+                                    let mut shared_state = shared_state.write();
+                                    for diagnostic in diagnostics {
+                                        let history = shared_state.diagnostics.entry(diagnostic.path.clone()).or_default();
+                                        history.suffix = diagnostic.suffix.clone();
+                                        history.push(diagnostic.value);
+                                    }
+                                }
+                                _ => {}
+                            }
+                            let _ = shared_state.read().runtime_response.send(response);
+                        }
+                        exit = wait_for_runtime(&mut runtime_process) => break exit,
+                    }
+                };
+
+                client.disconnect();
+
+                // a panic is written right before the process exits, wait for the rest of it
+                if let Some(stderr_reader) = stderr_reader {
+                    let _ = tokio::time::timeout(Duration::from_secs(1), stderr_reader).await;
+                }
+                let crash_report = match exit {
+                    Ok(status) if status.success() => None,
+                    Ok(status) => Some(CrashReport::new(status, stderr_tail.lines())),
+                    Err(err) => {
+                        log::error!("lost track of the runtime process: {}", err);
+                        None
+                    }
+                };
+
+                let mut shared_state = shared_state.write();
+                let restart = crash_report.is_some()
+                    && shared_state.auto_restart
+                    && started.elapsed() >= MIN_UPTIME_FOR_RESTART;
+                if !restart {
+                    shared_state.runtime_exited(crash_report);
+                    break;
+                }
+
+                let crash_report = crash_report.unwrap();
+                log::error!(
+                    "runtime crashed with {}, restarting it\n{}",
+                    crash_report.status,
+                    crash_report.panic.as_deref().unwrap_or_default()
+                );
+                reload_scene = shared_state.last_saved_scene.clone();
+                shared_state.start_runtime();
             }
         }
     });
@@ -259,9 +870,6 @@ fn RuntimeWindow<'a>(cx: Scope<'a>) -> Element {
         view {
             class: "w-full h-full bg-transparent",
             tabindex: 0,
-            onclick: move |_| {
-                runtime_sender.send(EditorToRuntimeMsg::Shutdown);
-            },
             onlayout: move |event| {
                 runtime_sender.send(EditorToRuntimeMsg::LayoutChange {
                     width: event.rect.width(),
@@ -288,6 +896,11 @@ fn RuntimeWindow<'a>(cx: Scope<'a>) -> Element {
                     state: event.element_state
                 });
             },
+            onwheel: move |event| {
+                runtime_sender.send(EditorToRuntimeMsg::MouseWheel {
+                    delta: event.delta
+                });
+            },
             oninput: move |event| {
                 runtime_sender.send(EditorToRuntimeMsg::ReceivedCharacter {
                     char: event.text.clone()
@@ -1,11 +1,19 @@
+use std::path::Path;
+
 use dioxus::prelude::*;
+use roth_shared::LoadMode;
 use tpaint::{components::image::Image, events::ClickEvent, prelude::*};
 
-use crate::{app::SharedState, drawer::DrawerContext};
+use crate::{
+    app::{asset_path, SharedState},
+    drawer::DrawerContext,
+};
 
 #[derive(Debug, PartialEq)]
 enum AssetType {
     Folder,
+    /// a `.scn.ron` the runtime can load
+    Scene,
     Unknown,
 }
 
@@ -36,6 +44,8 @@ pub fn AssetBrowser(cx: Scope) -> Element {
                 }
                 let asset_type = if path.is_dir() {
                     AssetType::Folder
+                } else if file_name.ends_with(".scn.ron") {
+                    AssetType::Scene
                 } else {
                     AssetType::Unknown
                 };
@@ -63,10 +73,15 @@ pub fn AssetBrowser(cx: Scope) -> Element {
                                 key: "{asset.path}",
                                 asset: &asset,
                                 onclick: move |_event: Event<ClickEvent>| {
-                                    if asset.asset_type == AssetType::Folder {
-                                        current_path_state.set(asset.path.clone());
+                                    match asset.asset_type {
+                                        AssetType::Folder => current_path_state.set(asset.path.clone()),
+                                        AssetType::Scene => load_scene(shared_state, &asset.path, LoadMode::Replace),
+                                        AssetType::Unknown => {}
                                     }
                                 },
+                                onadd: move |_event: Event<ClickEvent>| {
+                                    load_scene(shared_state, &asset.path, LoadMode::Additive);
+                                },
                             }
                         }
                     }
@@ -76,6 +91,14 @@ pub fn AssetBrowser(cx: Scope) -> Element {
     }
 }
 
+/// Asks the runtime to load the scene at the absolute `path`, which has to be in the asset folder.
+fn load_scene(shared_state: &UseSharedState<SharedState>, path: &str, mode: LoadMode) {
+    let mut shared_state = shared_state.write();
+    if let Some(asset_path) = asset_path(&shared_state.asset_root(), Path::new(path)) {
+        shared_state.load_scene(asset_path, mode);
+    }
+}
+
 // asset component
 
 #[component]
@@ -84,25 +107,42 @@ fn AssetItem<'a>(
     asset: &'a Asset,
     // current_path_state: &'a UseState<Option<String>>,
     onclick: EventHandler<'a, Event<ClickEvent>>,
+    // adds a scene to the one that's open instead of replacing it
+    onadd: EventHandler<'a, Event<ClickEvent>>,
 ) -> Element {
     let src = match asset.asset_type {
         AssetType::Folder => "./assets/folder.svg".to_string(),
-        AssetType::Unknown => "./assets/file-unknown.svg".to_string(),
+        AssetType::Scene | AssetType::Unknown => "./assets/file-unknown.svg".to_string(),
     };
 
     render! {
         view {
-            class: "w-84 h-84 p-8 hover:bg-zinc-700 flex-col justify-center items-center text-white rounded-5",
-            onclick: move |evt| onclick.call(evt),
+            class: "flex-col items-center",
+
+            view {
+                class: "w-84 h-84 p-8 hover:bg-zinc-700 flex-col justify-center items-center text-white rounded-5",
+                onclick: move |evt| onclick.call(evt),
 
-            Image {
-                src: src,
-                class: "mb-4",
+                Image {
+                    src: src,
+                    class: "mb-4",
+                }
+
+                view {
+                    class: "text-white text-12 mt-4 justify-center",
+                    "{asset.name}"
+                }
             }
 
-            view {
-                class: "text-white text-12 mt-4 justify-center",
-                "{asset.name}"
+            if asset.asset_type == AssetType::Scene {
+                rsx! {
+                    view {
+                        class: "text-zinc-400 text-12 hover:text-white",
+                        tabindex: 0,
+                        onclick: move |evt| onadd.call(evt),
+                        "Add to scene"
+                    }
+                }
             }
         }
     }
@@ -1,45 +1,53 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use dioxus::prelude::*;
+use roth_shared::LogLevel;
 use tpaint::prelude::*;
 
 use crate::{app::SharedState, drawer::DrawerContext};
 
+/// Lines the console keeps, older ones are dropped.
+pub const MAX_LOG_LINES: usize = 1000;
+
+/// A record the runtime logged, see `RuntimeToEditorMsg::Log`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+    pub timestamp: SystemTime,
+}
+
+/// `HH:MM:SS.mmm` in UTC
+pub fn format_time(timestamp: SystemTime) -> String {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() % (24 * 60 * 60);
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+impl LogLine {
+    fn level_class(&self) -> &'static str {
+        match self.level {
+            LogLevel::Error => "text-red-400",
+            LogLevel::Warn => "text-yellow-400",
+            LogLevel::Info => "text-green-400",
+            LogLevel::Debug => "text-blue-400",
+            LogLevel::Trace => "text-zinc-400",
+        }
+    }
+}
+
 pub fn Console(cx: Scope) -> Element {
     let drawer_ctx = use_context::<DrawerContext>(cx).unwrap();
     let shared_state = use_shared_state::<SharedState>(cx).unwrap();
-
-    // let shared_state = shared_state.read();
-    let lines = use_state(cx, || String::new());
-
-    use_future(cx, (shared_state,), |(shared_state,)| {
-        to_owned![lines];
-        async move {
-            let Some(mut runtime_output) = shared_state.write_silent().runtime_output.take() else {
-                return;
-            };
-            while let Some(line) = runtime_output.recv().await {
-                // println!("yo! {}", line);
-                let mut l = lines.make_mut();
-                l.push_str(&line);
-                l.push_str("\n");
-                // lines.push(line);
-            }
-        }
-    });
-
-    // if let Some(mut runtime_output) = shared_state.write_silent().runtime_output.take() {
-    //     cx.spawn({});
-    //     //     use_coroutine(cx, |mut _rx: UnboundedReceiver<()>| {
-    //     //         to_owned![lines];
-    //     //         async move {
-    //     //             while let Some(line) = runtime_output.recv().await {
-    //     //                 let mut l = lines.make_mut();
-    //     //                 l.push_str(&line);
-    //     //                 l.push_str("\n");
-    //     //                 // lines.push(line);
-    //     //             }
-    //     //         }
-    //     //     });
-    // }
+    let shared_state = shared_state.read();
 
     render! {
         view {
@@ -48,14 +56,40 @@ pub fn Console(cx: Scope) -> Element {
             view {
                 class: "w-full flex-col",
 
-                lines.lines().map(|line| rsx! {
-                    view {
-                        class: "w-full text-white text-12",
-                        "{line}"
+                shared_state.runtime_logs.iter().map(|line| {
+                    let time = format_time(line.timestamp);
+                    let level = line.level.as_str();
+                    let level_class = line.level_class();
+                    let fields = line
+                        .fields
+                        .iter()
+                        .map(|(name, value)| format!(" {}={}", name, value))
+                        .collect::<String>();
+
+                    rsx! {
+                        view {
+                            class: "w-full gap-x-8 text-12",
+
+                            view {
+                                class: "text-zinc-500",
+                                "{time}"
+                            }
+                            view {
+                                class: "w-40 {level_class}",
+                                "{level}"
+                            }
+                            view {
+                                class: "text-zinc-400",
+                                "{line.target}"
+                            }
+                            view {
+                                class: "text-white",
+                                "{line.message}{fields}"
+                            }
+                        }
                     }
                 })
             }
-
         }
     }
 }
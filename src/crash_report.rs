@@ -0,0 +1,122 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use dioxus::prelude::*;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tpaint::prelude::*;
+
+use crate::app::SharedState;
+
+/// Lines of the runtime's stderr that are kept around for a [`CrashReport`].
+const STDERR_LINES: usize = 200;
+
+/// Why the runtime stopped when nobody asked it to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    /// e.g. `exit status: 101`, or why it was stopped
+    pub status: String,
+    /// the panic message and backtrace, when it panicked
+    pub panic: Option<String>,
+    /// the last lines the runtime wrote to stderr
+    pub stderr: Vec<String>,
+}
+
+impl CrashReport {
+    pub fn new(status: impl fmt::Display, stderr: Vec<String>) -> Self {
+        Self {
+            status: status.to_string(),
+            panic: panic_message(&stderr),
+            stderr,
+        }
+    }
+}
+
+/// Everything from the last `thread '..' panicked at` line on, that's the message followed by the
+/// backtrace when `RUST_BACKTRACE` is set.
+fn panic_message(stderr: &[String]) -> Option<String> {
+    let start = stderr
+        .iter()
+        .rposition(|line| line.starts_with("thread '") && line.contains("' panicked at "))?;
+    Some(stderr[start..].join("\n"))
+}
+
+/// The last lines of the runtime's stderr, filled by [`StderrTail::capture`].
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail(Arc<Mutex<VecDeque<String>>>);
+
+impl StderrTail {
+    /// Keeps the last lines of `stderr` while passing them on to the editor's own stderr, finishes
+    /// when the runtime closes it.
+    pub async fn capture(self, stderr: impl AsyncRead + Unpin) {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            eprintln!("{}", line);
+            let mut tail = self.0.lock().unwrap();
+            if tail.len() >= STDERR_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Shown in place of the viewport after the runtime crashed.
+pub fn CrashReportView(cx: Scope) -> Element {
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let Some(crash_report) = shared_state.read().crash_report.clone() else {
+        return None;
+    };
+    let status = crash_report.status;
+    // without a panic the last thing it wrote is the best guess at what went wrong
+    let lines = match crash_report.panic {
+        Some(panic) => panic.lines().map(|it| it.to_string()).collect(),
+        None => crash_report.stderr,
+    };
+
+    render! {
+        view {
+            class: "w-full h-full flex-col gap-y-8 bg-zinc-900 p-20",
+
+            view {
+                class: "w-full justify-between items-center",
+
+                view {
+                    class: "text-red-400 text-14",
+                    "The runtime crashed ({status})"
+                }
+
+                view {
+                    class: "text-white text-14",
+                    tabindex: 0,
+                    onclick: move |_| {
+                        shared_state.write().start_runtime();
+                    },
+
+                    "Restart"
+                }
+            }
+
+            view {
+                class: "w-full h-full overflow-y-scroll scrollbar-default",
+
+                view {
+                    class: "w-full flex-col text-zinc-300",
+
+                    lines.iter().map(|line| rsx! {
+                        view {
+                            class: "w-full text-12",
+                            "{line}"
+                        }
+                    })
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+use dioxus::prelude::*;
+use tpaint::prelude::*;
+
+use crate::{app::SharedState, drawer::DrawerContext};
+
+/// Values the graphs keep of every diagnostic, older ones are dropped.
+pub const DIAGNOSTICS_HISTORY: usize = 120;
+const GRAPH_HEIGHT: f64 = 24.0;
+
+/// The last values the runtime sent of one of its diagnostics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiagnosticHistory {
+    pub suffix: String,
+    pub values: VecDeque<f64>,
+}
+
+impl DiagnosticHistory {
+    pub fn push(&mut self, value: f64) {
+        if self.values.len() >= DIAGNOSTICS_HISTORY {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Bar heights of the graph, scaled so the largest value fills it.
+    fn bar_heights(&self) -> Vec<f64> {
+        let max = self.values.iter().copied().fold(0.0, f64::max);
+        if max <= 0.0 {
+            return vec![1.0; self.values.len()];
+        }
+        self.values
+            .iter()
+            .map(|value| (value / max * GRAPH_HEIGHT).max(1.0).round())
+            .collect()
+    }
+}
+
+pub fn Diagnostics(cx: Scope) -> Element {
+    let drawer_ctx = use_context::<DrawerContext>(cx).unwrap();
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let shared_state = shared_state.read();
+
+    render! {
+        view {
+            class: "h-{drawer_ctx.height} w-full overflow-y-scroll scrollbar-default",
+
+            if shared_state.diagnostics.is_empty() {
+                rsx! {
+                    view {
+                        class: "w-full text-zinc-400 text-12",
+                        "The runtime doesn't send any diagnostics"
+                    }
+                }
+            }
+
+            view {
+                class: "w-full flex-col gap-y-8",
+
+                shared_state.diagnostics.iter().map(|(path, history)| {
+                    let latest = history.values.back().copied().unwrap_or_default();
+                    rsx! {
+                        view {
+                            key: "{path}",
+                            class: "w-full gap-x-8 items-center text-12",
+
+                            view {
+                                class: "w-150 text-zinc-400",
+                                "{path}"
+                            }
+                            view {
+                                class: "w-80 text-white",
+                                "{latest:.1} {history.suffix}"
+                            }
+                            view {
+                                class: "h-{GRAPH_HEIGHT} items-end bg-zinc-800 rounded-2",
+
+                                history.bar_heights().into_iter().map(|height| rsx! {
+                                    view {
+                                        class: "w-2 h-{height} bg-green-400",
+                                    }
+                                })
+                            }
+                        }
+                    }
+                })
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
 use dioxus::prelude::*;
 use tpaint::{components::image::Image, events::ClickEvent, prelude::*};
 
-use crate::{asset_browser::AssetBrowser, console::Console};
+use crate::{
+    asset_browser::AssetBrowser, console::Console, diagnostics::Diagnostics,
+    notifications::NotificationHistory,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tab {
     AssetBrowser,
     Console,
+    Diagnostics,
+    Notifications,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -27,7 +32,7 @@ pub fn Drawer(cx: Scope) -> Element {
             view {
                 class: "gap-x-8 px-5",
 
-                for tab in [Tab::AssetBrowser, Tab::Console] {
+                for tab in [Tab::AssetBrowser, Tab::Console, Tab::Diagnostics, Tab::Notifications] {
                     view {
                         class: "bg-zinc-800 hover:bg-zinc-900 active:bg-zinc-900 text-white p-9 px-8 text-14 rounded-t-5",
                         is_active: tab_state.get() == &tab,
@@ -38,6 +43,8 @@ pub fn Drawer(cx: Scope) -> Element {
                         match tab {
                             Tab::AssetBrowser => rsx!(" Assets "),
                             Tab::Console => rsx!(" Console "),
+                            Tab::Diagnostics => rsx!(" Diagnostics "),
+                            Tab::Notifications => rsx!(" Notifications "),
                         }
                     }
                 }
@@ -47,10 +54,11 @@ pub fn Drawer(cx: Scope) -> Element {
             view {
                 class: "bg-zinc-900 p-16 text-white rounded-5",
 
-                if tab_state.get() == &Tab::AssetBrowser {
-                    rsx! { AssetBrowser {} }
-                } else {
-                    rsx! { Console {} }
+                match tab_state.get() {
+                    Tab::AssetBrowser => rsx! { AssetBrowser {} },
+                    Tab::Console => rsx! { Console {} },
+                    Tab::Diagnostics => rsx! { Diagnostics {} },
+                    Tab::Notifications => rsx! { NotificationHistory {} },
                 }
             }
         }
@@ -0,0 +1,834 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bevy::ecs::entity::Entity;
+use roth_shared::{ComponentData, EditorToRuntimeMsg, RuntimeToEditorMsg};
+use winit::{
+    event::ElementState,
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+use crate::{
+    runtime_client::{RequestError, RuntimeClient},
+    world_model::WorldModel,
+};
+
+/// Steps the history keeps, older ones can't be undone anymore.
+const MAX_STEPS: usize = 100;
+/// Edits of the same field this close to each other are undone in one go, e.g. while a float is
+/// being scrubbed.
+const MERGE_WINDOW: Duration = Duration::from_millis(500);
+
+// restored through the hierarchy instead, their values point at entities that might not exist
+const PARENT: &str = "bevy_hierarchy::components::parent::Parent";
+const CHILDREN: &str = "bevy_hierarchy::components::children::Children";
+const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+/// A message of a step, sent as it is but for the entities that were spawned again since.
+#[derive(Debug, Clone)]
+enum Op {
+    Send(EditorToRuntimeMsg),
+    /// spawns `entity` again, the entity it answers with stands in for it from then on
+    Spawn {
+        entity: Entity,
+        components: Vec<ComponentData>,
+    },
+    /// like `Spawn` for each of the copies in `spawned`
+    Duplicate {
+        entity: Entity,
+        recursive: bool,
+        spawned: Vec<Entity>,
+    },
+}
+
+impl Op {
+    fn msg(&self) -> EditorToRuntimeMsg {
+        match self {
+            Op::Send(msg) => msg.clone(),
+            Op::Spawn { components, .. } => EditorToRuntimeMsg::SpawnEntity {
+                components: components.clone(),
+            },
+            Op::Duplicate {
+                entity, recursive, ..
+            } => EditorToRuntimeMsg::DuplicateEntity {
+                entity: *entity,
+                recursive: *recursive,
+            },
+        }
+    }
+}
+
+/// An edit, as the messages that make it and the ones that take it back.
+#[derive(Debug, Clone)]
+struct Step {
+    redo: Vec<Op>,
+    undo: Vec<Op>,
+    /// the field a field edit changed, further edits of it are merged into this step for a while
+    field: Option<String>,
+    edited_at: Instant,
+}
+
+impl Step {
+    /// `undo` is what [`inverse`] worked out before `msg` was sent, `None` when it's answered with
+    /// an error or can't be undone.
+    fn new(
+        msg: EditorToRuntimeMsg,
+        undo: Option<Vec<Op>>,
+        answer: &RuntimeToEditorMsg,
+    ) -> Option<Self> {
+        let (redo, undo) = match (msg, answer) {
+            (_, RuntimeToEditorMsg::Error { .. }) => return None,
+            (
+                EditorToRuntimeMsg::SpawnEntity { components },
+                RuntimeToEditorMsg::EntitySpawned { entity },
+            ) => (
+                Op::Spawn {
+                    entity: *entity,
+                    components,
+                },
+                vec![Op::Send(EditorToRuntimeMsg::DespawnEntity {
+                    entity: *entity,
+                    recursive: true,
+                })],
+            ),
+            (
+                EditorToRuntimeMsg::DuplicateEntity { entity, recursive },
+                RuntimeToEditorMsg::EntityDuplicated {
+                    entity: copy,
+                    spawned,
+                },
+            ) => (
+                Op::Duplicate {
+                    entity,
+                    recursive,
+                    spawned: spawned.clone(),
+                },
+                vec![Op::Send(EditorToRuntimeMsg::DespawnEntity {
+                    entity: *copy,
+                    recursive: true,
+                })],
+            ),
+            (msg, _) => (Op::Send(msg), undo?),
+        };
+
+        let field = match redo.msg() {
+            EditorToRuntimeMsg::SetField {
+                entity,
+                component,
+                path,
+                ..
+            } => Some(format!("{:?} {} {}", entity, component, path)),
+            EditorToRuntimeMsg::SetResourceField { resource, path, .. } => {
+                Some(format!("{} {}", resource, path))
+            }
+            _ => None,
+        };
+        Some(Self {
+            redo: vec![redo],
+            undo,
+            field,
+            edited_at: Instant::now(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Steps {
+    undo: VecDeque<Step>,
+    redo: Vec<Step>,
+    /// entities that were despawned and spawned again by an undo or redo, with the one that was
+    /// spawned in their place
+    respawned: HashMap<Entity, Entity>,
+    /// set while a step is replayed, so an undo doesn't start in the middle of a redo
+    replaying: bool,
+    /// the undo and redo steps from before `Play`, while playing
+    edit_mode: Option<(VecDeque<Step>, Vec<Step>)>,
+}
+
+impl Steps {
+    fn record(&mut self, step: Step) {
+        self.redo.clear();
+        if let Some(last) = self.undo.back_mut() {
+            let merges = step.field.is_some()
+                && last.field == step.field
+                && step.edited_at.duration_since(last.edited_at) <= MERGE_WINDOW;
+            if merges {
+                // it still undoes to the value from before the first edit
+                last.redo = step.redo;
+                last.edited_at = step.edited_at;
+                return;
+            }
+        }
+        if self.undo.len() >= MAX_STEPS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(step);
+    }
+
+    /// The entity that stands in for `entity` now.
+    fn resolve(&self, mut entity: Entity) -> Entity {
+        while let Some(respawned) = self.respawned.get(&entity) {
+            entity = *respawned;
+        }
+        entity
+    }
+
+    fn respawn(&mut self, entity: Entity, respawned: Entity) {
+        let current = self.resolve(entity);
+        self.respawned.insert(current, respawned);
+    }
+
+    /// `msg` with the entities that were spawned again since it was recorded.
+    fn remap(&self, mut msg: EditorToRuntimeMsg) -> EditorToRuntimeMsg {
+        match &mut msg {
+            EditorToRuntimeMsg::InsertComponent { entity, .. }
+            | EditorToRuntimeMsg::RemoveComponent { entity, .. }
+            | EditorToRuntimeMsg::DespawnEntity { entity, .. }
+            | EditorToRuntimeMsg::DuplicateEntity { entity, .. }
+            | EditorToRuntimeMsg::SetField { entity, .. } => *entity = self.resolve(*entity),
+            EditorToRuntimeMsg::SetParent { child, parent } => {
+                *child = self.resolve(*child);
+                *parent = parent.map(|parent| self.resolve(parent));
+            }
+            _ => {}
+        }
+        msg
+    }
+}
+
+/// Edits made in the editor, undone and redone by sending the runtime the messages that take them
+/// back or make them again.
+///
+/// Cheap to clone like [`RuntimeClient`], take a clone out of [`crate::app::SharedState`] before
+/// awaiting anything.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    steps: Arc<Mutex<Steps>>,
+}
+
+impl History {
+    /// Sends `msg` and records it once the runtime answered, `undo` is what [`inverse`] returned
+    /// for it. Resolves with the answer, errors included.
+    async fn edit(
+        self,
+        runtime: RuntimeClient,
+        msg: EditorToRuntimeMsg,
+        undo: Option<Vec<Op>>,
+    ) -> Result<RuntimeToEditorMsg, RequestError> {
+        let answer = runtime.request(msg.clone()).await?;
+        if let Some(step) = Step::new(msg, undo, &answer) {
+            self.steps.lock().unwrap().record(step);
+        }
+        Ok(answer)
+    }
+
+    pub async fn undo(self, runtime: RuntimeClient) {
+        self.replay(runtime, true).await;
+    }
+
+    pub async fn redo(self, runtime: RuntimeClient) {
+        self.replay(runtime, false).await;
+    }
+
+    async fn replay(self, runtime: RuntimeClient, undo: bool) {
+        let step = {
+            let mut steps = self.steps.lock().unwrap();
+            if steps.replaying {
+                return;
+            }
+            let step = if undo {
+                steps.undo.pop_back()
+            } else {
+                steps.redo.pop()
+            };
+            let Some(step) = step else {
+                return;
+            };
+            steps.replaying = true;
+            step
+        };
+
+        let ops = if undo { &step.undo } else { &step.redo };
+        let mut result = Ok(());
+        for op in ops {
+            result = self.run(&runtime, op).await;
+            if result.is_err() {
+                break;
+            }
+        }
+
+        let mut steps = self.steps.lock().unwrap();
+        steps.replaying = false;
+        match result {
+            // the runtime's error is shown already, the step is dropped as it's only half done
+            Err(err) => log::error!("failed to {}: {}", if undo { "undo" } else { "redo" }, err),
+            Ok(()) if undo => steps.redo.push(step),
+            Ok(()) => steps.undo.push_back(step),
+        }
+    }
+
+    async fn run(&self, runtime: &RuntimeClient, op: &Op) -> Result<(), String> {
+        let msg = self.steps.lock().unwrap().remap(op.msg());
+        let answer = runtime.request(msg).await.map_err(|err| err.to_string())?;
+
+        let mut steps = self.steps.lock().unwrap();
+        match (op, answer) {
+            (_, RuntimeToEditorMsg::Error { message, .. }) => return Err(message),
+            (Op::Spawn { entity, .. }, RuntimeToEditorMsg::EntitySpawned { entity: spawned }) => {
+                steps.respawn(*entity, spawned);
+            }
+            (
+                Op::Duplicate { spawned, .. },
+                RuntimeToEditorMsg::EntityDuplicated {
+                    spawned: respawned, ..
+                },
+            ) => {
+                for (entity, respawned) in spawned.iter().zip(respawned) {
+                    steps.respawn(*entity, respawned);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Sets the steps made so far aside, the game starts with a history of its own.
+    pub fn start_playing(&self) {
+        let mut steps = self.steps.lock().unwrap();
+        if steps.edit_mode.is_none() {
+            let undo = std::mem::take(&mut steps.undo);
+            let redo = std::mem::take(&mut steps.redo);
+            steps.edit_mode = Some((undo, redo));
+        }
+    }
+
+    /// Goes back to the steps from before playing, the runtime put the world back the way it was
+    /// then too.
+    pub fn stop_playing(&self) {
+        let mut steps = self.steps.lock().unwrap();
+        if let Some((undo, redo)) = steps.edit_mode.take() {
+            steps.undo = undo;
+            steps.redo = redo;
+        }
+    }
+
+    /// Forgets every step, e.g. when the entities they refer to are gone.
+    pub fn clear(&self) {
+        let mut steps = self.steps.lock().unwrap();
+        steps.undo.clear();
+        steps.redo.clear();
+        steps.respawned.clear();
+        steps.edit_mode = None;
+    }
+}
+
+/// Sends `msg` as an edit that can be undone, resolving with the runtime's answer once it's
+/// recorded.
+pub fn edit(
+    history: &History,
+    runtime: Option<RuntimeClient>,
+    world: &WorldModel,
+    resources: &[ComponentData],
+    msg: EditorToRuntimeMsg,
+) -> impl Future<Output = Result<RuntimeToEditorMsg, RequestError>> + Send + 'static {
+    let history = history.clone();
+    let undo = inverse(world, resources, &msg);
+    async move {
+        let runtime = runtime.ok_or(RequestError::Disconnected)?;
+        history.edit(runtime, msg, undo).await
+    }
+}
+
+/// What takes `msg` back, from the world as it is before `msg` is sent. `None` when it can't be
+/// undone, e.g. when the editor has no value for what it overwrites, or when it depends on the
+/// answer like `SpawnEntity` does.
+fn inverse(
+    world: &WorldModel,
+    resources: &[ComponentData],
+    msg: &EditorToRuntimeMsg,
+) -> Option<Vec<Op>> {
+    let component = |entity: Entity, type_name: &str| {
+        world
+            .components(entity)?
+            .iter()
+            .find(|it| it.type_name == type_name)
+    };
+    let resource = |type_name: &str| resources.iter().find(|it| it.type_name == type_name);
+
+    let msg = match msg {
+        EditorToRuntimeMsg::InsertComponent {
+            entity,
+            component: inserted,
+        } => match component(*entity, &inserted.type_name) {
+            Some(existing) => EditorToRuntimeMsg::InsertComponent {
+                entity: *entity,
+                component: restorable(existing)?,
+            },
+            None => EditorToRuntimeMsg::RemoveComponent {
+                entity: *entity,
+                type_name: inserted.type_name.clone(),
+            },
+        },
+        EditorToRuntimeMsg::RemoveComponent { entity, type_name } => {
+            EditorToRuntimeMsg::InsertComponent {
+                entity: *entity,
+                component: restorable(component(*entity, type_name)?)?,
+            }
+        }
+        EditorToRuntimeMsg::SetField {
+            entity,
+            component: type_name,
+            path,
+            ..
+        } => EditorToRuntimeMsg::SetField {
+            entity: *entity,
+            component: type_name.clone(),
+            path: path.clone(),
+            value: component(*entity, type_name)?
+                .value
+                .clone()?
+                .path_mut(path)?
+                .clone(),
+        },
+        EditorToRuntimeMsg::SetResource { resource: set } => EditorToRuntimeMsg::SetResource {
+            resource: restorable(resource(&set.type_name)?)?,
+        },
+        EditorToRuntimeMsg::SetResourceField {
+            resource: type_name,
+            path,
+            ..
+        } => EditorToRuntimeMsg::SetResourceField {
+            resource: type_name.clone(),
+            path: path.clone(),
+            value: resource(type_name)?.value.clone()?.path_mut(path)?.clone(),
+        },
+        EditorToRuntimeMsg::SetParent { child, .. } => EditorToRuntimeMsg::SetParent {
+            child: *child,
+            parent: world.parent(*child),
+        },
+        EditorToRuntimeMsg::DespawnEntity { entity, recursive } => {
+            // spawning it again without them would report success for half an entity
+            if !unrestorable_components(world, *entity, *recursive).is_empty() {
+                return None;
+            }
+            let mut ops = vec![];
+            respawn(world, *entity, *recursive, &mut ops);
+            return Some(ops);
+        }
+        _ => return None,
+    };
+    Some(vec![Op::Send(msg)])
+}
+
+/// `component` if it has a value to restore it with.
+fn restorable(component: &ComponentData) -> Option<ComponentData> {
+    component.value.is_some().then(|| component.clone())
+}
+
+/// Components of `entity`, and of its descendants when `recursive`, the editor has no value for,
+/// e.g. strong asset handles. Despawning it can't be undone when there are any.
+pub fn unrestorable_components(world: &WorldModel, entity: Entity, recursive: bool) -> Vec<String> {
+    let mut type_names = world
+        .components(entity)
+        .unwrap_or_default()
+        .iter()
+        .filter(|it| it.value.is_none() && it.type_name != PARENT && it.type_name != CHILDREN)
+        .map(|it| it.type_name.clone())
+        .collect::<Vec<_>>();
+    if recursive {
+        for child in world.children(entity) {
+            type_names.extend(unrestorable_components(world, *child, recursive));
+        }
+    }
+    type_names.sort();
+    type_names.dedup();
+    type_names
+}
+
+/// Spawns `entity` again as it is in `world`, under its parent. Its children are spawned again as
+/// well when `recursive`, otherwise they're only moved back under it with the transforms they had.
+fn respawn(world: &WorldModel, entity: Entity, recursive: bool, ops: &mut Vec<Op>) {
+    let components = world
+        .components(entity)
+        .unwrap_or_default()
+        .iter()
+        .filter(|it| it.type_name != PARENT && it.type_name != CHILDREN)
+        .cloned()
+        .collect();
+    ops.push(Op::Spawn { entity, components });
+    if let Some(parent) = world.parent(entity) {
+        ops.push(Op::Send(EditorToRuntimeMsg::SetParent {
+            child: entity,
+            parent: Some(parent),
+        }));
+    }
+
+    for child in world.children(entity) {
+        if recursive {
+            respawn(world, *child, recursive, ops);
+        } else {
+            ops.push(Op::Send(EditorToRuntimeMsg::SetParent {
+                child: *child,
+                parent: Some(entity),
+            }));
+            // the despawn left them with their local transform and reparenting keeps the global
+            // one, neither is what they had under `entity`
+            let transform = world
+                .components(*child)
+                .and_then(|components| components.iter().find(|it| it.type_name == TRANSFORM))
+                .and_then(restorable);
+            if let Some(transform) = transform {
+                ops.push(Op::Send(EditorToRuntimeMsg::InsertComponent {
+                    entity: *child,
+                    component: transform,
+                }));
+            }
+        }
+    }
+}
+
+/// Turns key presses into undo and redo. Key events don't say which modifiers are held, so they're
+/// tracked here.
+#[derive(Debug, Default)]
+pub struct UndoShortcuts {
+    control: bool,
+    shift: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    Undo,
+    Redo,
+}
+
+impl UndoShortcuts {
+    /// Ctrl+Z undoes, Ctrl+Shift+Z redoes.
+    pub fn key(&mut self, physical_key: PhysicalKey, state: ElementState) -> Option<HistoryAction> {
+        let pressed = state == ElementState::Pressed;
+        match physical_key {
+            PhysicalKey::Code(KeyCode::ControlLeft | KeyCode::ControlRight) => {
+                self.control = pressed
+            }
+            PhysicalKey::Code(KeyCode::ShiftLeft | KeyCode::ShiftRight) => self.shift = pressed,
+            PhysicalKey::Code(KeyCode::KeyZ) if pressed && self.control => {
+                return Some(if self.shift {
+                    HistoryAction::Redo
+                } else {
+                    HistoryAction::Undo
+                });
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use roth_shared::{ErrorKind, ReflectValue, Request, Response};
+
+    use super::*;
+
+    const NAME: &str = "bevy_core::name::Name";
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw(index)
+    }
+
+    fn name(name: &str) -> ComponentData {
+        ComponentData {
+            type_name: NAME.to_string(),
+            value: Some(ReflectValue::String(name.to_string())),
+        }
+    }
+
+    fn transform(x: f32) -> ComponentData {
+        ComponentData {
+            type_name: TRANSFORM.to_string(),
+            value: Some(ReflectValue::Struct(vec![(
+                "translation".to_string(),
+                ReflectValue::Struct(vec![("x".to_string(), ReflectValue::F32(x))]),
+            )])),
+        }
+    }
+
+    fn set_x(x: f32) -> EditorToRuntimeMsg {
+        EditorToRuntimeMsg::SetField {
+            entity: entity(1),
+            component: TRANSFORM.to_string(),
+            path: "translation.x".to_string(),
+            value: ReflectValue::F32(x),
+        }
+    }
+
+    /// The messages don't implement `PartialEq`.
+    fn debug<T: std::fmt::Debug>(value: T) -> String {
+        format!("{:?}", value)
+    }
+
+    fn msgs(ops: &[Op]) -> String {
+        debug(ops.iter().map(Op::msg).collect::<Vec<_>>())
+    }
+
+    /// A parent (1) with a name and a transform, and its child (2).
+    fn world() -> WorldModel {
+        let mut world = WorldModel::default();
+        world.apply(&RuntimeToEditorMsg::Entities {
+            entities: vec![
+                (entity(1), vec![name("Parent"), transform(1.0)]),
+                (entity(2), vec![name("Child"), transform(2.0)]),
+            ],
+            hierarchy: vec![(entity(1), vec![entity(2)])],
+        });
+        world
+    }
+
+    fn step(msg: EditorToRuntimeMsg) -> Step {
+        let undo = inverse(&world(), &[], &msg);
+        Step::new(
+            msg,
+            undo,
+            &RuntimeToEditorMsg::FieldSet {
+                entity: entity(1),
+                component: TRANSFORM.to_string(),
+                path: "translation.x".to_string(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn field_edits_in_a_row_merge() {
+        let mut steps = Steps::default();
+        steps.record(step(set_x(2.0)));
+        steps.record(step(set_x(3.0)));
+        assert_eq!(steps.undo.len(), 1);
+        assert_eq!(msgs(&steps.undo[0].redo), debug(vec![set_x(3.0)]));
+        // still back to where the first edit started
+        assert_eq!(msgs(&steps.undo[0].undo), debug(vec![set_x(1.0)]));
+
+        let mut late = step(set_x(4.0));
+        late.edited_at += MERGE_WINDOW * 2;
+        steps.record(late);
+        assert_eq!(steps.undo.len(), 2);
+
+        let mut other_field = step(set_x(5.0));
+        other_field.field = Some("something else".to_string());
+        steps.record(other_field);
+        assert_eq!(steps.undo.len(), 3);
+    }
+
+    #[test]
+    fn inverse_of_insert_remove_and_reparent() {
+        let world = world();
+        let undo = |msg| msgs(&inverse(&world, &[], &msg).unwrap());
+
+        let new_component = ComponentData {
+            type_name: "Health".to_string(),
+            value: Some(ReflectValue::U32(3)),
+        };
+        assert_eq!(
+            undo(EditorToRuntimeMsg::InsertComponent {
+                entity: entity(1),
+                component: new_component,
+            }),
+            debug(vec![EditorToRuntimeMsg::RemoveComponent {
+                entity: entity(1),
+                type_name: "Health".to_string(),
+            }])
+        );
+
+        let insert_name = |component| EditorToRuntimeMsg::InsertComponent {
+            entity: entity(1),
+            component,
+        };
+        assert_eq!(
+            undo(insert_name(name("Renamed"))),
+            debug(vec![insert_name(name("Parent"))])
+        );
+        assert_eq!(
+            undo(EditorToRuntimeMsg::RemoveComponent {
+                entity: entity(1),
+                type_name: NAME.to_string(),
+            }),
+            debug(vec![insert_name(name("Parent"))])
+        );
+
+        assert_eq!(
+            undo(EditorToRuntimeMsg::SetParent {
+                child: entity(2),
+                parent: None,
+            }),
+            debug(vec![EditorToRuntimeMsg::SetParent {
+                child: entity(2),
+                parent: Some(entity(1)),
+            }])
+        );
+        assert_eq!(
+            undo(EditorToRuntimeMsg::SetParent {
+                child: entity(1),
+                parent: Some(entity(2)),
+            }),
+            debug(vec![EditorToRuntimeMsg::SetParent {
+                child: entity(1),
+                parent: None,
+            }])
+        );
+    }
+
+    #[test]
+    fn undoing_a_despawn_restores_the_childrens_transforms() {
+        let undo = inverse(
+            &world(),
+            &[],
+            &EditorToRuntimeMsg::DespawnEntity {
+                entity: entity(1),
+                recursive: false,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            msgs(&undo),
+            debug(vec![
+                EditorToRuntimeMsg::SpawnEntity {
+                    components: vec![name("Parent"), transform(1.0)],
+                },
+                EditorToRuntimeMsg::SetParent {
+                    child: entity(2),
+                    parent: Some(entity(1)),
+                },
+                EditorToRuntimeMsg::InsertComponent {
+                    entity: entity(2),
+                    component: transform(2.0),
+                },
+            ])
+        );
+        // once it's spawned again, the child goes under the new entity
+        let mut steps = Steps::default();
+        steps.respawn(entity(1), entity(7));
+        assert_eq!(
+            debug(steps.remap(undo[1].msg())),
+            debug(EditorToRuntimeMsg::SetParent {
+                child: entity(2),
+                parent: Some(entity(7)),
+            })
+        );
+    }
+
+    #[test]
+    fn despawns_losing_components_are_not_undoable() {
+        let mesh = ComponentData {
+            type_name: "bevy_asset::handle::Handle<bevy_render::mesh::mesh::Mesh>".to_string(),
+            value: None,
+        };
+        // like `world`, but the child has a mesh
+        let mut world = WorldModel::default();
+        world.apply(&RuntimeToEditorMsg::Entities {
+            entities: vec![
+                (entity(1), vec![name("Parent"), transform(1.0)]),
+                (entity(2), vec![name("Child"), transform(2.0), mesh.clone()]),
+            ],
+            hierarchy: vec![(entity(1), vec![entity(2)])],
+        });
+        let despawn = |entity, recursive| {
+            inverse(
+                &world,
+                &[],
+                &EditorToRuntimeMsg::DespawnEntity { entity, recursive },
+            )
+        };
+
+        assert_eq!(
+            unrestorable_components(&world, entity(1), true),
+            vec![mesh.type_name.clone()]
+        );
+        assert!(despawn(entity(1), true).is_none());
+        assert!(despawn(entity(2), false).is_none());
+        // the child stays, so only the parent has to be spawned again
+        assert!(unrestorable_components(&world, entity(1), false).is_empty());
+        assert!(despawn(entity(1), false).is_some());
+    }
+
+    /// Answers `FieldSet` to everything, and `Error` to the `SetField`s of `x` when it's `failing`.
+    fn runtime(failing: Option<f32>) -> (RuntimeClient, Arc<Mutex<Vec<EditorToRuntimeMsg>>>) {
+        let (client, mut receiver) = RuntimeClient::new();
+        let sent = Arc::new(Mutex::new(vec![]));
+        tokio::spawn({
+            let client = client.clone();
+            let sent = sent.clone();
+            async move {
+                while let Some(Request { id, msg }) = receiver.recv().await {
+                    let fails = matches!(
+                        (&msg, failing),
+                        (EditorToRuntimeMsg::SetField { value: ReflectValue::F32(x), .. }, Some(failing))
+                            if *x == failing
+                    );
+                    let answer = if fails {
+                        RuntimeToEditorMsg::Error {
+                            request_id: Some(id),
+                            kind: ErrorKind::InvalidValue,
+                            message: "can't set it".to_string(),
+                        }
+                    } else {
+                        RuntimeToEditorMsg::FieldSet {
+                            entity: entity(1),
+                            component: TRANSFORM.to_string(),
+                            path: "translation.x".to_string(),
+                        }
+                    };
+                    sent.lock().unwrap().push(msg);
+                    client.resolve(&Response {
+                        request_id: Some(id),
+                        msg: answer,
+                    });
+                }
+            }
+        });
+        (client, sent)
+    }
+
+    fn stack_sizes(history: &History) -> (usize, usize) {
+        let steps = history.steps.lock().unwrap();
+        (steps.undo.len(), steps.redo.len())
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_move_steps_between_the_stacks() {
+        let history = History::default();
+        let (runtime, sent) = runtime(Some(9.0));
+        let world = world();
+        let edit = |msg| edit(&history, Some(runtime.clone()), &world, &[], msg);
+
+        edit(set_x(2.0)).await.unwrap();
+        // errors aren't recorded
+        edit(set_x(9.0)).await.unwrap();
+        assert_eq!(stack_sizes(&history), (1, 0));
+
+        history.clone().undo(runtime.clone()).await;
+        assert_eq!(stack_sizes(&history), (0, 1));
+        assert_eq!(debug(sent.lock().unwrap().last()), debug(Some(set_x(1.0))));
+        // nothing left to undo
+        history.clone().undo(runtime.clone()).await;
+        assert_eq!(stack_sizes(&history), (0, 1));
+
+        history.clone().redo(runtime.clone()).await;
+        assert_eq!(stack_sizes(&history), (1, 0));
+        assert_eq!(debug(sent.lock().unwrap().last()), debug(Some(set_x(2.0))));
+
+        history.clone().undo(runtime.clone()).await;
+        assert_eq!(stack_sizes(&history), (0, 1));
+        // a new edit makes what was undone unreachable
+        edit(EditorToRuntimeMsg::RemoveComponent {
+            entity: entity(1),
+            type_name: NAME.to_string(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(stack_sizes(&history), (1, 0));
+    }
+}
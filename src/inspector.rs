@@ -1,188 +1,449 @@
 use bevy::ecs::entity::Entity;
 use dioxus::prelude::*;
 use roth_shared::{
-    ron::{value::Float, Number, Value},
-    RonComponent, RonComponentSerialized, RuntimeToEditorMsg,
+    handshake::Capabilities,
+    schema::{TypeKind, VariantSchema},
+    value::VariantValue,
+    ComponentData, EditorToRuntimeMsg, ReflectValue,
 };
 use tpaint::{components::image::Image, prelude::*};
 
 use crate::app::SharedState;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Components,
+    Resources,
+}
+
+/// Where edits made in [`ComponentProperties`] are sent to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PropertyTarget {
+    Component(Entity),
+    Resource,
+}
+
+impl PropertyTarget {
+    /// Sets the field at `path` of the component or resource called `type_name`.
+    fn set_field(self, type_name: String, path: String, value: ReflectValue) -> EditorToRuntimeMsg {
+        match self {
+            PropertyTarget::Component(entity) => EditorToRuntimeMsg::SetField {
+                entity,
+                component: type_name,
+                path,
+                value,
+            },
+            PropertyTarget::Resource => EditorToRuntimeMsg::SetResourceField {
+                resource: type_name,
+                path,
+                value,
+            },
+        }
+    }
+}
+
 #[component]
 pub fn Inspector(cx: Scope) -> Element {
     let shared_state = use_shared_state::<SharedState>(cx).unwrap();
-    let components_state = use_state::<Vec<RonComponent>>(cx, move || vec![]);
-    let selected_component_state = use_state::<Option<(Entity, RonComponent)>>(cx, move || None);
-
-    use_effect(cx, (), move |()| {
-        to_owned![shared_state, components_state, selected_component_state];
-        async move {
-            let mut rx = shared_state.read().runtime_response.subscribe();
-            while let Ok(RuntimeToEditorMsg::Entities { entities }) = rx.recv().await {
-                let Some(selected_entity) = shared_state.read().selected_entity else {
-                    components_state.set(vec![]);
-                    continue;
-                };
+    let tab_state = use_state::<Tab>(cx, || Tab::Components);
 
-                // reset selected component if it doesn't exist anymore
-                if let Some((entity, _)) = selected_component_state.get() {
-                    if !entities.iter().any(|(it, _)| *it == *entity) {
-                        selected_component_state.set(None);
-                    }
-                }
+    render! {
+        view {
+            class: "w-20% h-full bg-zinc-900  text-white rounded-5 flex-col",
 
-                let selected_component = entities
-                    .iter()
-                    .find(|(entity, _)| *entity == selected_entity);
+            // tabs
+            view {
+                class: "gap-x-8 px-5 pt-5",
 
-                if let Some((_, components)) = selected_component {
-                    components_state
-                        .set(components.iter().map(|it| RonComponent::from(it)).collect());
-                } else {
-                    components_state.set(vec![]);
+                for tab in [Tab::Components, Tab::Resources] {
+                    view {
+                        class: "hover:bg-zinc-800 active:bg-zinc-800 text-white p-9 px-8 text-14 rounded-t-5",
+                        is_active: tab_state.get() == &tab,
+                        onclick: move |_| {
+                            let shared_state = shared_state.read();
+                            if tab == Tab::Resources && shared_state.runtime_capabilities.contains(Capabilities::RESOURCES) {
+                                // resources aren't streamed like entities, refresh them whenever they're shown
+                                shared_state.send_to_runtime(EditorToRuntimeMsg::GetResources);
+                            }
+                            tab_state.set(tab);
+                        },
+
+                        match tab {
+                            Tab::Components => rsx!(" Components "),
+                            Tab::Resources => rsx!(" Resources "),
+                        }
+                    }
                 }
             }
+
+            if tab_state.get() == &Tab::Components {
+                rsx! { EntityComponents {} }
+            } else {
+                rsx! { Resources {} }
+            }
         }
-    });
-    let selected_entity = shared_state.read().selected_entity;
+    }
+}
+
+#[component]
+fn EntityComponents(cx: Scope) -> Element {
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    // entity and type name of the component that is expanded
+    let selected_component_state = use_state::<Option<(Entity, String)>>(cx, move || None);
+
+    let selected_entity = shared_state.read().selected_entity?;
+    let components: Vec<ComponentData> = shared_state
+        .read()
+        .world
+        .components(selected_entity)
+        .map(|components| components.to_vec())
+        .unwrap_or_default();
 
     render! {
-        view {
-            class: "w-20% h-full bg-zinc-900  text-white rounded-5 flex-col",
+        components.into_iter().enumerate().map(|(i, component)| {
+            let type_name = component.type_name.clone();
+            let is_expanded =
+                selected_component_state.get().as_ref() == Some(&(selected_entity, type_name.clone()));
 
-            for (i, component) in components_state.get().iter().enumerate() {
-                view {
+            rsx! {
+                PropertiesEntry {
                     key: "{i}-{component.short_name()}",
-                    class: "text-white flex-col  w-full border-1 border-zinc-800 px-10 py-8",
+                    target: PropertyTarget::Component(selected_entity),
+                    component: component,
+                    is_expanded: is_expanded,
+                    on_toggle: move |_| {
+                        selected_component_state.set(Some((selected_entity, type_name.clone())));
+                    },
+                }
+            }
+        })
+    }
+}
 
-                    view {
-                        class: "text-white w-full items-center text-14",
-                        onclick: move |_event: Event<_>| {
-                            selected_component_state.set(Some((
-                                selected_entity.unwrap(),
-                                component.clone()
-                            )));
-                        },
+#[component]
+fn Resources(cx: Scope) -> Element {
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    // type name of the resource that is expanded
+    let selected_resource_state = use_state::<Option<String>>(cx, move || None);
 
-                        Image {
-                            class: "w-16 h-16 mr-10",
-                            src: "./assets/toy-brick.svg".into(),
-                        }
+    let resources = shared_state.read().resources.clone();
 
-                        "{component.short_name()}",
+    render! {
+        resources.into_iter().map(|resource| {
+            let type_name = resource.type_name.clone();
+            let is_expanded = selected_resource_state.get().as_ref() == Some(&type_name);
 
-                        if component.value == Value::Unit {
-                            rsx! {
-                                view {
-                                    class: "text-14 text-zinc-700 ml-10",
-                                    "Computed"
-                                }
-                            }
+            rsx! {
+                PropertiesEntry {
+                    key: "{type_name}",
+                    target: PropertyTarget::Resource,
+                    component: resource,
+                    is_expanded: is_expanded,
+                    on_toggle: move |_| {
+                        selected_resource_state.set(Some(type_name.clone()));
+                    },
+                }
+            }
+        })
+    }
+}
+
+#[component]
+fn PropertiesEntry<'a>(
+    cx: Scope<'a>,
+    target: PropertyTarget,
+    component: ComponentData,
+    is_expanded: bool,
+    on_toggle: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+
+    render! {
+        view {
+            class: "text-white flex-col  w-full border-1 border-zinc-800 px-10 py-8",
+
+            view {
+                class: "text-white w-full items-center text-14",
+                onclick: move |_event: Event<_>| {
+                    on_toggle.call(());
+                },
+
+                Image {
+                    class: "w-16 h-16 mr-10",
+                    src: "./assets/toy-brick.svg".into(),
+                }
+
+                "{component.short_name()}",
+
+                if component.value.is_none() {
+                    rsx! {
+                        view {
+                            class: "text-14 text-zinc-700 ml-10",
+                            "Computed"
                         }
                     }
+                }
 
-                    if selected_component_state.get().as_ref().map(|(entity, selected_component)| selected_entity.unwrap() == *entity && *selected_component.type_name == *component.type_name).unwrap_or(false) {
-                        rsx!{
-                            ComponentProperties {
-                                entity: selected_entity.unwrap(),
-                                ron_component: component.clone(),
-                            }
+                if let PropertyTarget::Component(entity) = *target {
+                    rsx! {
+                        view {
+                            class: "text-14 text-zinc-500 hover:text-white ml-10",
+                            tabindex: 0,
+                            onclick: move |_| {
+                                shared_state.read().send_edit(EditorToRuntimeMsg::RemoveComponent {
+                                    entity,
+                                    type_name: component.type_name.clone(),
+                                });
+                            },
+                            "Remove"
                         }
                     }
                 }
             }
 
+            if *is_expanded {
+                rsx!{
+                    ComponentProperties {
+                        target: *target,
+                        component: component.clone(),
+                    }
+                }
+            }
         }
     }
 }
 
 #[component]
-fn ComponentProperties(cx: Scope, entity: Entity, ron_component: RonComponent) -> Element {
-    let ron_component = cx.use_hook(|| ron_component.clone());
+fn ComponentProperties(cx: Scope, target: PropertyTarget, component: ComponentData) -> Element {
     let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let value = component.value.as_ref()?;
 
-    let type_name = ron_component.type_name.clone();
-    let components_mut = ron_component.components_mut();
-    let init_ptr = components_mut as *mut Value;
-
+    /// One row per field of `value`, whose reflect path within the component is `path` and whose
+    /// type is `type_path`, when the runtime's schema has it.
     fn recurse_value<'a>(
-        value: &'a mut Value,
+        value: &'a ReflectValue,
+        path: String,
+        type_path: Option<String>,
         state: UseSharedState<SharedState>,
-        init_ptr: *mut Value,
-        entity: Entity,
+        target: PropertyTarget,
         type_name: String,
-        mut parent_keys: Vec<String>,
     ) -> Vec<LazyNodes<'a, 'a>> {
-        let ptr = value as *mut Value;
+        let schema = state.read().type_registry.clone();
+        let kind = schema
+            .as_deref()
+            .zip(type_path.as_deref())
+            .and_then(|(schema, type_path)| schema.get(type_path))
+            // its value follows its own serde implementation, not the shape of the type
+            .filter(|it| !it.reflect_serialize)
+            .map(|it| &it.kind);
 
-        match value {
-            Value::Map(map) => map
-                .iter_mut()
-                .map(|(key, value)| {
-                    let Value::String(key) = key else {
-                        unreachable!("Key should always be a string")
-                    };
-
-                    let state = state.clone();
-                    let type_name = type_name.clone();
-                    let key: String = key.clone();
-                    parent_keys.push(key.clone());
-                    let parent_keys = parent_keys.clone();
-
-                    rsx! {
-                        view {
-                            class: "w-full flex-row justify-between items-start text-white",
-                            key: "{entity:?}-{type_name}-{parent_keys:?}-{key}",
-                            "{key}: ",
-                            view {
-                                class: "flex-col",
-                                recurse_value(value, state, init_ptr, entity, type_name, parent_keys).into_iter()
-                            }
-                        }
-                    }
-                })
-                .collect(),
-            Value::Number(number) => {
-                let node = match number {
-                    Number::Float(val) => {
-                        rsx! {
-                            view {
-                                class: "text-white",
-                                key: "{entity:?}-{type_name}-{parent_keys:?}-{val.get():.2}",
-                                onclick: move |_event: Event<_>| {
-                                    let new_value = val.get() + 1.0;
-                                    unsafe {
-                                        (*ptr) = Value::Number(Number::Float(Float::new(new_value)));
-                                    }
-
-                                    state.read().send_to_runtime(roth_shared::EditorToRuntimeMsg::InsertComponent {
-                                        entity,
-                                        component: RonComponentSerialized {
-                                            type_name: type_name.clone(),
-                                            value: roth_shared::serde_json::to_string(unsafe { &*init_ptr }).unwrap()
-                                        }
-                                    });
-                                },
-                                "{val.get():.2}",
-                            }
-                        }
+        let mut rows = vec![];
+        let fields = match value {
+            ReflectValue::Struct(fields) => named_fields(fields, &path, |name| match kind? {
+                TypeKind::Struct { fields } => fields
+                    .iter()
+                    .find(|it| it.name == name)
+                    .map(|it| it.type_path.clone()),
+                _ => None,
+            }),
+            ReflectValue::Tuple(items) => match kind {
+                // arrays are serialized like tuples, but only take `[0]` in a reflect path
+                Some(TypeKind::Array { item, .. }) => indexed_fields(items, |index| {
+                    (format!("{}[{}]", path, index), Some(item.clone()))
+                }),
+                Some(TypeKind::Tuple { fields } | TypeKind::TupleStruct { fields }) => {
+                    indexed_fields(items, |index| {
+                        (
+                            field_path(&path, &index.to_string()),
+                            fields.get(index).cloned(),
+                        )
+                    })
+                }
+                _ => indexed_fields(items, |index| (field_path(&path, &index.to_string()), None)),
+            },
+            ReflectValue::Seq(items) => indexed_fields(items, |index| {
+                let item = match kind {
+                    Some(TypeKind::List { item } | TypeKind::Array { item, .. }) => {
+                        Some(item.clone())
                     }
-                    Number::Integer(val) => {
-                        rsx! {
-                            view {
-                                class: "text-white",
-                                key: "{entity:?}-{type_name}-{parent_keys:?}-{val}",
-                                "{val}",
-                            }
-                        }
+                    _ => None,
+                };
+                (format!("{}[{}]", path, index), item)
+            }),
+            ReflectValue::Newtype(value) => {
+                let field = match kind {
+                    Some(TypeKind::TupleStruct { fields }) => fields.first().cloned(),
+                    _ => None,
+                };
+                vec![("0".to_string(), field_path(&path, "0"), &**value, field)]
+            }
+            ReflectValue::Option(Some(value)) => {
+                let field = variant_fields(kind, "Some").and_then(|it| it.first().cloned());
+                vec![("Some".to_string(), field_path(&path, "0"), &**value, field)]
+            }
+            ReflectValue::Enum {
+                variant,
+                value: variant_value,
+            } => {
+                rows.push(read_only(variant.clone(), target, &type_name, &path));
+                let variant_schema = match kind {
+                    Some(TypeKind::Enum { variants }) => {
+                        variants.iter().find(|it| it.name() == variant.as_str())
                     }
+                    _ => None,
                 };
+                match variant_value {
+                    VariantValue::Unit => vec![],
+                    VariantValue::Newtype(value) => {
+                        let field =
+                            variant_fields(kind, variant).and_then(|it| it.first().cloned());
+                        vec![("0".to_string(), field_path(&path, "0"), &**value, field)]
+                    }
+                    VariantValue::Tuple(items) => indexed_fields(items, |index| {
+                        let field =
+                            variant_fields(kind, variant).and_then(|it| it.get(index).cloned());
+                        (field_path(&path, &index.to_string()), field)
+                    }),
+                    VariantValue::Struct(fields) => {
+                        named_fields(fields, &path, |name| match variant_schema? {
+                            VariantSchema::Struct { fields, .. } => fields
+                                .iter()
+                                .find(|it| it.name == name)
+                                .map(|it| it.type_path.clone()),
+                            _ => None,
+                        })
+                    }
+                }
+            }
+            ReflectValue::F32(val) => {
+                return vec![float_field(
+                    *val as f64,
+                    |value| ReflectValue::F32(value as f32),
+                    state,
+                    target,
+                    type_name,
+                    path,
+                )];
+            }
+            ReflectValue::F64(val) => {
+                return vec![float_field(
+                    *val,
+                    ReflectValue::F64,
+                    state,
+                    target,
+                    type_name,
+                    path,
+                )];
+            }
+            ReflectValue::I8(_)
+            | ReflectValue::I16(_)
+            | ReflectValue::I32(_)
+            | ReflectValue::I64(_)
+            | ReflectValue::I128(_)
+            | ReflectValue::U8(_)
+            | ReflectValue::U16(_)
+            | ReflectValue::U32(_)
+            | ReflectValue::U64(_)
+            | ReflectValue::U128(_) => {
+                let val = integer_to_string(value);
+                return vec![rsx! {
+                    view {
+                        class: "text-white",
+                        key: "{target:?}-{type_name}-{path}-{val}",
+                        "{val}",
+                    }
+                }];
+            }
+            ReflectValue::Unit => {
+                return vec![read_only("()".to_string(), target, &type_name, &path)]
+            }
+            ReflectValue::Bool(val) => {
+                return vec![read_only(val.to_string(), target, &type_name, &path)];
+            }
+            ReflectValue::Char(val) => {
+                return vec![read_only(format!("{:?}", val), target, &type_name, &path)];
+            }
+            ReflectValue::String(val) => {
+                return vec![read_only(format!("{:?}", val), target, &type_name, &path)];
+            }
+            ReflectValue::Option(None) => {
+                return vec![read_only("None".to_string(), target, &type_name, &path)];
+            }
+            ReflectValue::Bytes(bytes) => {
+                return vec![read_only(
+                    format!("{} bytes", bytes.len()),
+                    target,
+                    &type_name,
+                    &path,
+                )];
+            }
+            other => {
+                log::debug!("not showing the fields of {:?}", other);
+                return vec![read_only("...".to_string(), target, &type_name, &path)];
+            }
+        };
+
+        rows.extend(fields.into_iter().map(|(key, path, value, type_path)| {
+            let state = state.clone();
+            let type_name = type_name.clone();
+
+            rsx! {
+                view {
+                    class: "w-full flex-row justify-between items-start text-white",
+                    key: "{target:?}-{type_name}-{path}",
+                    "{key}: ",
+                    view {
+                        class: "flex-col",
+                        recurse_value(value, path.clone(), type_path, state, target, type_name).into_iter()
+                    }
+                }
+            }
+        }));
+        rows
+    }
 
-                vec![node]
+    /// A value that can't be edited yet.
+    fn read_only<'a>(
+        val: String,
+        target: PropertyTarget,
+        type_name: &str,
+        path: &str,
+    ) -> LazyNodes<'a, 'a> {
+        let key = format!("{:?}-{}-{}-{}", target, type_name, path, val);
+        rsx! {
+            view {
+                class: "text-zinc-400",
+                key: "{key}",
+                "{val}",
             }
-            unimplemented => {
-                println!("unimplemented: {:?}", unimplemented);
-                vec![]
+        }
+    }
+
+    /// Only sends the one field, so whatever else the runtime changed in the meantime is kept.
+    fn float_field<'a>(
+        val: f64,
+        to_value: fn(f64) -> ReflectValue,
+        state: UseSharedState<SharedState>,
+        target: PropertyTarget,
+        type_name: String,
+        path: String,
+    ) -> LazyNodes<'a, 'a> {
+        rsx! {
+            view {
+                class: "text-white",
+                key: "{target:?}-{type_name}-{path}-{val:.2}",
+                onclick: move |_event: Event<_>| {
+                    let msg = target.set_field(type_name.clone(), path.clone(), to_value(val + 1.0));
+                    let edit = state.read().edit(msg);
+                    let type_name = type_name.clone();
+                    let path = path.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = edit.await {
+                            log::error!("failed to update {} of {}: {}", path, type_name, err);
+                        }
+                    });
+                },
+                "{val:.2}",
             }
         }
     }
@@ -190,7 +451,80 @@ fn ComponentProperties(cx: Scope, entity: Entity, ron_component: RonComponent) -
     render! {
         view {
             class: "w-full flex-col gap-10 mt-10",
-            recurse_value(components_mut, shared_state.to_owned(), init_ptr, *entity, type_name.clone(), vec![type_name.clone()]).into_iter()
+            recurse_value(value, String::new(), Some(component.type_name.clone()), shared_state.to_owned(), *target, component.type_name.clone()).into_iter()
         }
     }
 }
+
+/// A row of [`ComponentProperties`], as its label, its reflect path, its value and its type path.
+type Field<'a> = (String, String, &'a ReflectValue, Option<String>);
+
+/// The fields of a struct or struct variant at `path`, `field_type` is the type path of a field.
+fn named_fields<'a>(
+    fields: &'a [(String, ReflectValue)],
+    path: &str,
+    field_type: impl Fn(&str) -> Option<String>,
+) -> Vec<Field<'a>> {
+    fields
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.clone(),
+                field_path(path, name),
+                value,
+                field_type(name),
+            )
+        })
+        .collect()
+}
+
+/// The items of a tuple, list or array, `path_and_type` is the reflect path and type path of an item.
+fn indexed_fields(
+    items: &[ReflectValue],
+    path_and_type: impl Fn(usize) -> (String, Option<String>),
+) -> Vec<Field<'_>> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let (path, type_path) = path_and_type(index);
+            (index.to_string(), path, value, type_path)
+        })
+        .collect()
+}
+
+/// Type paths of the fields of the tuple variant `variant` of the enum `kind`.
+fn variant_fields<'a>(kind: Option<&'a TypeKind>, variant: &str) -> Option<&'a Vec<String>> {
+    let TypeKind::Enum { variants } = kind? else {
+        return None;
+    };
+    variants.iter().find_map(|it| match it {
+        VariantSchema::Tuple { name, fields } if name == variant => Some(fields),
+        _ => None,
+    })
+}
+
+/// Reflect path of the field `field` of the value at `parent`, e.g. `translation.x`.
+fn field_path(parent: &str, field: &str) -> String {
+    if parent.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", parent, field)
+    }
+}
+
+fn integer_to_string(value: &ReflectValue) -> String {
+    match value {
+        ReflectValue::I8(val) => val.to_string(),
+        ReflectValue::I16(val) => val.to_string(),
+        ReflectValue::I32(val) => val.to_string(),
+        ReflectValue::I64(val) => val.to_string(),
+        ReflectValue::I128(val) => val.to_string(),
+        ReflectValue::U8(val) => val.to_string(),
+        ReflectValue::U16(val) => val.to_string(),
+        ReflectValue::U32(val) => val.to_string(),
+        ReflectValue::U64(val) => val.to_string(),
+        ReflectValue::U128(val) => val.to_string(),
+        _ => String::new(),
+    }
+}
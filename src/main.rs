@@ -9,9 +9,15 @@ use winit::event::WindowEvent;
 mod app;
 mod asset_browser;
 mod console;
+mod crash_report;
+mod diagnostics;
 mod drawer;
+mod history;
 mod inspector;
+mod notifications;
+mod runtime_client;
 mod scene_viewer;
+mod world_model;
 
 type UserEvent = ();
 
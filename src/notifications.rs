@@ -0,0 +1,210 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant, SystemTime},
+};
+
+use dioxus::prelude::*;
+use tpaint::prelude::*;
+
+use crate::{app::SharedState, console::format_time, drawer::DrawerContext};
+
+/// Notifications the history keeps, older ones are dropped.
+pub const MAX_NOTIFICATIONS: usize = 100;
+/// How long a notification stays up as a toast unless it's dismissed.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Something that went wrong, e.g. a `RuntimeToEditorMsg::Error`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    id: u64,
+    /// what kind of failure it is, e.g. `not found`
+    pub title: String,
+    pub message: String,
+    pub timestamp: SystemTime,
+    shown_at: Instant,
+    dismissed: bool,
+}
+
+impl Notification {
+    fn toast_expires_at(&self) -> Instant {
+        self.shown_at + TOAST_DURATION
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Notifications {
+    history: VecDeque<Notification>,
+    next_id: u64,
+}
+
+impl Notifications {
+    pub fn push(&mut self, title: impl Into<String>, message: impl Into<String>) {
+        if self.history.len() >= MAX_NOTIFICATIONS {
+            self.history.pop_front();
+        }
+        self.history.push_back(Notification {
+            id: self.next_id,
+            title: title.into(),
+            message: message.into(),
+            timestamp: SystemTime::now(),
+            shown_at: Instant::now(),
+            dismissed: false,
+        });
+        self.next_id += 1;
+    }
+
+    /// Hides the toast, it stays in the history.
+    fn dismiss(&mut self, id: u64) {
+        if let Some(notification) = self.history.iter_mut().find(|it| it.id == id) {
+            notification.dismissed = true;
+        }
+    }
+
+    /// The notifications that are still shown as toasts, oldest first.
+    fn toasts(&self) -> impl Iterator<Item = &Notification> {
+        let now = Instant::now();
+        self.history
+            .iter()
+            .filter(move |it| !it.dismissed && it.toast_expires_at() > now)
+    }
+
+    fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Recent notifications, shown above the viewport until they expire or are clicked away.
+pub fn Toasts(cx: Scope) -> Element {
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    // set once the oldest toast expired, so it's rendered without it
+    let expired_state = use_state::<Option<u64>>(cx, || None);
+
+    let toasts = shared_state
+        .read()
+        .notifications
+        .toasts()
+        .cloned()
+        .collect::<Vec<_>>();
+    let next_expiry = toasts
+        .first()
+        .map(|toast| (toast.id, toast.toast_expires_at()));
+
+    use_future(cx, (&next_expiry,), |(next_expiry,)| {
+        to_owned![expired_state];
+        async move {
+            if let Some((id, expires_at)) = next_expiry {
+                tokio::time::sleep_until(expires_at.into()).await;
+                expired_state.set(Some(id));
+            }
+        }
+    });
+
+    render! {
+        view {
+            class: "w-full flex-col gap-y-4",
+
+            toasts.into_iter().map(|toast| {
+                let id = toast.id;
+                rsx! {
+                    view {
+                        key: "{id}",
+                        class: "w-full bg-zinc-900 hover:bg-zinc-800 p-8 gap-x-8 text-14",
+                        tabindex: 0,
+                        onclick: move |_| {
+                            shared_state.write().notifications.dismiss(id);
+                        },
+
+                        view {
+                            class: "text-red-400",
+                            "{toast.title}"
+                        }
+                        view {
+                            class: "text-white",
+                            "{toast.message}"
+                        }
+                    }
+                }
+            })
+        }
+    }
+}
+
+/// Every notification that was shown, in the drawer.
+pub fn NotificationHistory(cx: Scope) -> Element {
+    let drawer_ctx = use_context::<DrawerContext>(cx).unwrap();
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let notifications = shared_state
+        .read()
+        .notifications
+        .history
+        .iter()
+        .rev()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    render! {
+        view {
+            class: "h-{drawer_ctx.height} w-full flex-col gap-y-8",
+
+            view {
+                class: "w-full justify-between text-12",
+
+                if notifications.is_empty() {
+                    rsx! {
+                        view {
+                            class: "text-zinc-400",
+                            "Nothing went wrong yet"
+                        }
+                    }
+                } else {
+                    rsx! {
+                        view {
+                            class: "text-zinc-400",
+                            "{notifications.len()} notifications, newest first"
+                        }
+                    }
+                }
+
+                view {
+                    class: "text-white",
+                    tabindex: 0,
+                    onclick: move |_| {
+                        shared_state.write().notifications.clear();
+                    },
+                    "Clear"
+                }
+            }
+
+            view {
+                class: "w-full h-full overflow-y-scroll scrollbar-default",
+
+                view {
+                    class: "w-full flex-col",
+
+                    notifications.iter().map(|notification| {
+                        let time = format_time(notification.timestamp);
+                        rsx! {
+                            view {
+                                key: "{notification.id}",
+                                class: "w-full gap-x-8 text-12",
+
+                                view {
+                                    class: "text-zinc-500",
+                                    "{time}"
+                                }
+                                view {
+                                    class: "text-red-400",
+                                    "{notification.title}"
+                                }
+                                view {
+                                    class: "text-white",
+                                    "{notification.message}"
+                                }
+                            }
+                        }
+                    })
+                }
+            }
+        }
+    }
+}
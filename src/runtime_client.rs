@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use roth_shared::{EditorToRuntimeMsg, Request, RequestId, Response, RuntimeToEditorMsg};
+use tokio::sync::{mpsc, oneshot};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestError {
+    /// The runtime went away before it answered.
+    Disconnected,
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Disconnected => write!(f, "runtime disconnected before answering"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+type PendingRequests = HashMap<RequestId, oneshot::Sender<RuntimeToEditorMsg>>;
+
+/// Sends messages to the runtime and lets callers await the answer to their own request.
+///
+/// Cheap to clone, take a clone out of [`crate::app::SharedState`] before awaiting anything.
+#[derive(Clone)]
+pub struct RuntimeClient {
+    sender: mpsc::UnboundedSender<Request>,
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Mutex<PendingRequests>>,
+}
+
+impl RuntimeClient {
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<Request>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let client = Self {
+            sender,
+            next_id: Arc::new(AtomicU64::new(0)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        };
+        (client, receiver)
+    }
+
+    fn next_id(&self) -> RequestId {
+        RequestId(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Sends a message without waiting for an answer, any answer still ends up on the broadcast channel.
+    pub fn send(&self, msg: EditorToRuntimeMsg) -> RequestId {
+        let id = self.next_id();
+        let _ = self.sender.send(Request { id, msg });
+        id
+    }
+
+    /// Sends a message and resolves with the first response the runtime sends for it.
+    pub async fn request(
+        &self,
+        msg: EditorToRuntimeMsg,
+    ) -> Result<RuntimeToEditorMsg, RequestError> {
+        let id = self.next_id();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+
+        if self.sender.send(Request { id, msg }).is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(RequestError::Disconnected);
+        }
+
+        rx.await.map_err(|_| RequestError::Disconnected)
+    }
+
+    /// Hands a response to whoever is awaiting it, if anyone.
+    pub fn resolve(&self, response: &Response) {
+        let Some(request_id) = response.request_id else {
+            return;
+        };
+
+        if let Some(tx) = self.pending.lock().unwrap().remove(&request_id) {
+            let _ = tx.send(response.msg.clone());
+        }
+    }
+
+    /// Fails every request that is still waiting for an answer.
+    pub fn disconnect(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+}
@@ -1,37 +1,32 @@
+use std::collections::HashSet;
+
 use bevy::ecs::entity::Entity;
 use dioxus::prelude::*;
-use roth_shared::{
-    ron::Value, EditorToRuntimeMsg, RonComponent, RonComponentSerialized, RuntimeToEditorMsg,
-};
+use roth_shared::{ComponentData, EditorToRuntimeMsg, ReflectValue, RuntimeToEditorMsg};
 use tpaint::{components::image::Image, prelude::*};
 
-use crate::app::SharedState;
+use crate::{app::SharedState, history, world_model::WorldModel};
 
 // in the future this should be editable in a config file
 const KNOWN_COMPONENT_NAMES: [&str; 4] = ["PointLight", "DirectionalLight", "Camera", "Mesh"];
 
-fn get_entity_name(entity: &Entity, components: &[RonComponent]) -> String {
+fn get_entity_name(entity: &Entity, components: &[ComponentData]) -> String {
     // first we check if any `Name` components exist
-    if let Some(component) = components
+    let name = components
         .iter()
-        .find(|it| it.name() == "bevy_core::name::Name")
-    {
-        let Value::Map(map) = component.components() else {
-            panic!("expected component value to always be a map");
-        };
-        let key = Value::String("name".to_string());
-        if let Value::String(name) = &map[&key] {
-            return format!("{} ({:?})", name, entity);
-        } else {
-            panic!("expected name to be a string");
-        }
+        .find(|it| it.type_name == "bevy_core::name::Name")
+        .and_then(|it| it.value.as_ref())
+        // `Name` serializes as a plain string, older versions reflect it as a struct
+        .and_then(|value| value.as_str().or_else(|| value.field("name")?.as_str()));
+    if let Some(name) = name {
+        return format!("{} ({:?})", name, entity);
     }
 
     // if not we check there are any main
     for known_component_name in KNOWN_COMPONENT_NAMES.iter() {
         if components
             .iter()
-            .any(|it| it.name().contains(known_component_name))
+            .any(|it| it.type_name.contains(known_component_name))
         {
             return format!("{} ({:?})", known_component_name, entity);
         }
@@ -40,46 +35,165 @@ fn get_entity_name(entity: &Entity, components: &[RonComponent]) -> String {
     format!("{:?}", entity)
 }
 
-pub fn SceneViewer(cx: Scope) -> Element {
-    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
-    let entities_state =
-        use_state::<Vec<(Entity, Vec<RonComponentSerialized>)>>(cx, move || vec![]);
+/// What the tree in [`SceneViewer`] has expanded and what's being dragged around in it.
+#[derive(Debug, Default)]
+struct TreeState {
+    expanded: HashSet<Entity>,
+    /// the entity the mouse went down on, it's reparented onto the row the mouse goes up on
+    dragged: Option<Entity>,
+}
 
-    use_effect(cx, shared_state, |shared_state| async move {
-        shared_state
-            .read()
-            .send_to_runtime(EditorToRuntimeMsg::GetEntities);
-    });
-
-    use_effect(cx, (), move |()| {
-        to_owned![shared_state, entities_state];
-        async move {
-            let mut rx = shared_state.read().runtime_response.subscribe();
-            while let Ok(RuntimeToEditorMsg::Entities { entities }) = rx.recv().await {
-                entities_state.set(entities);
-            }
+/// Rows of the tree with their depth, the children of collapsed entities are left out.
+fn visible_rows(world: &WorldModel, expanded: &HashSet<Entity>) -> Vec<(Entity, usize)> {
+    let mut rows = vec![];
+    let mut stack = world.roots().map(|entity| (entity, 0)).collect::<Vec<_>>();
+    stack.reverse();
+
+    while let Some((entity, depth)) = stack.pop() {
+        rows.push((entity, depth));
+        if expanded.contains(&entity) {
+            stack.extend(
+                world
+                    .children(entity)
+                    .iter()
+                    .rev()
+                    .map(|child| (*child, depth + 1)),
+            );
         }
-    });
+    }
+    rows
+}
+
+pub fn SceneViewer(cx: Scope) -> Element {
+    use_shared_state_provider(cx, TreeState::default);
+    let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let tree_state = use_shared_state::<TreeState>(cx).unwrap();
+    let state = shared_state.read();
+    let rows = visible_rows(&state.world, &tree_state.read().expanded);
+    // only offer to unparent when there is a parent to take it away from
+    let dragged_child = tree_state
+        .read()
+        .dragged
+        .filter(|entity| state.world.parent(*entity).is_some());
 
     render! {
         view {
             class: "w-20% bg-zinc-900 rounded-5 h-full  text-white overflow-y-scroll flex-col justify-start scrollbar-default gap-10 items-start",
+            // the drag ended somewhere other than a row
+            onmouseup: move |_| {
+                tree_state.write().dragged = None;
+            },
+
+            view {
+                class: "flex-row p-8 justify-between items-center w-full text-14 text-white",
+                "Entities",
+
+                view {
+                    class: "text-zinc-400 hover:text-white",
+                    tabindex: 0,
+                    onclick: move |_| {
+                        let spawn = shared_state.read().edit(EditorToRuntimeMsg::SpawnEntity {
+                            components: vec![ComponentData {
+                                type_name: "bevy_core::name::Name".to_string(),
+                                value: Some(ReflectValue::String("Entity".to_string())),
+                            }],
+                        });
+                        to_owned![shared_state];
+                        cx.spawn(async move {
+                            let spawned = spawn.await;
+                            if let Ok(RuntimeToEditorMsg::EntitySpawned { entity }) = spawned {
+                                shared_state.write().selected_entity = Some(entity);
+                            }
+                        });
+                    },
+                    "+ Create"
+                }
+            }
 
-            entities_state.iter().map(|(entity, components)| rsx! {
+            rows.into_iter().map(|(entity, depth)| rsx! {
                 Entity {
                     key: "{entity.index()}v{entity.generation()}",
                     entity: entity,
-                    components: components.iter().map(|it| RonComponent::from(it)).collect(),
+                    components: state.world.components(entity).unwrap_or_default().to_vec(),
+                    depth: depth,
+                    has_children: !state.world.children(entity).is_empty(),
                 }
             })
+
+            if let Some(child) = dragged_child {
+                rsx! {
+                    view {
+                        class: "p-8 w-full text-14 text-zinc-400 hover:text-white",
+                        onmouseup: move |_| {
+                            tree_state.write().dragged = None;
+                            shared_state
+                                .read()
+                                .send_edit(EditorToRuntimeMsg::SetParent { child, parent: None });
+                        },
+                        "Drop here to remove it from its parent"
+                    }
+                }
+            }
         }
     }
 }
 
 #[component]
-fn Entity<'a>(cx: Scope, entity: &'a Entity, components: Vec<RonComponent>) -> Element {
-    // let is_open = use_state(cx, || false);
+fn Entity(
+    cx: Scope,
+    entity: Entity,
+    components: Vec<ComponentData>,
+    depth: usize,
+    has_children: bool,
+) -> Element {
     let shared_state = use_shared_state::<SharedState>(cx).unwrap();
+    let tree_state = use_shared_state::<TreeState>(cx).unwrap();
+    let toggle = if tree_state.read().expanded.contains(entity) {
+        "-"
+    } else {
+        "+"
+    };
+    let indent = depth * 16;
+    let is_menu_open = use_state(cx, || false);
+    let despawn = move |recursive: bool| {
+        let mut shared_state = shared_state.write();
+        if shared_state.selected_entity == Some(*entity) {
+            shared_state.selected_entity = None;
+        }
+        let unrestorable =
+            history::unrestorable_components(&shared_state.world, *entity, recursive);
+        if !unrestorable.is_empty() {
+            shared_state.notifications.push(
+                "can't undo",
+                format!(
+                    "deleting {:?} can't be undone, the editor has no value for {}",
+                    entity,
+                    unrestorable.join(", ")
+                ),
+            );
+        }
+        shared_state.send_edit(EditorToRuntimeMsg::DespawnEntity {
+            entity: *entity,
+            recursive,
+        });
+        is_menu_open.set(false);
+    };
+    let duplicate = move |recursive: bool| {
+        is_menu_open.set(false);
+        let duplicate = shared_state
+            .read()
+            .edit(EditorToRuntimeMsg::DuplicateEntity {
+                entity: *entity,
+                recursive,
+            });
+        to_owned![shared_state];
+        cx.spawn(async move {
+            let duplicated = duplicate.await;
+            if let Ok(RuntimeToEditorMsg::EntityDuplicated { entity, .. }) = duplicated {
+                shared_state.write().selected_entity = Some(entity);
+            }
+        });
+    };
 
     render! {
     // view {
@@ -88,15 +202,103 @@ fn Entity<'a>(cx: Scope, entity: &'a Entity, components: Vec<RonComponent>) -> E
 
         view {
             class: "flex-row p-8 justify-between items-center w-full text-14 text-white active:bg-zinc-800",
-            is_active: "{shared_state.read().selected_entity == Some(**entity)}",
+            is_active: "{shared_state.read().selected_entity == Some(*entity)}",
             onclick: move |_event: Event<_>| {
-                shared_state.write().selected_entity = Some(**entity);
+                shared_state.write().selected_entity = Some(*entity);
+            },
+            onmousedown: move |_| {
+                tree_state.write().dragged = Some(*entity);
             },
-            "{get_entity_name(entity, components)}",
+            onmouseup: move |_| {
+                let Some(child) = tree_state.write().dragged.take() else {
+                    return;
+                };
+                if child == *entity {
+                    return;
+                }
+                // so the entity that was dropped stays in view
+                tree_state.write().expanded.insert(*entity);
+                shared_state.read().send_edit(EditorToRuntimeMsg::SetParent {
+                    child,
+                    parent: Some(*entity),
+                });
+            },
+
+            view {
+                class: "items-center",
 
-            Image {
-                class: "w-20 h-20",
-                src: "./assets/more-vertical.svg".to_string(),
+                view {
+                    class: "w-{indent}",
+                }
+
+                if *has_children {
+                    rsx! {
+                        view {
+                            class: "w-16 text-zinc-400 hover:text-white",
+                            tabindex: 0,
+                            onclick: move |_| {
+                                let mut tree_state = tree_state.write();
+                                if !tree_state.expanded.remove(entity) {
+                                    tree_state.expanded.insert(*entity);
+                                }
+                            },
+                            "{toggle}"
+                        }
+                    }
+                } else {
+                    rsx! {
+                        view {
+                            class: "w-16",
+                        }
+                    }
+                }
+
+                "{get_entity_name(entity, components)}",
+            }
+
+            view {
+                tabindex: 0,
+                onclick: move |_| {
+                    is_menu_open.set(!is_menu_open.get());
+                },
+
+                Image {
+                    class: "w-20 h-20",
+                    src: "./assets/more-vertical.svg".to_string(),
+                }
+            }
+        }
+
+        if *is_menu_open.get() {
+            rsx! {
+                view {
+                    class: "flex-col w-full px-16 text-14 text-zinc-400",
+
+                    view {
+                        class: "py-4 hover:text-white",
+                        tabindex: 0,
+                        onclick: move |_| duplicate(false),
+                        "Duplicate"
+                    }
+                    view {
+                        class: "py-4 hover:text-white",
+                        tabindex: 0,
+                        onclick: move |_| duplicate(true),
+                        "Duplicate with children"
+                    }
+                    view {
+                        class: "py-4 hover:text-white",
+                        tabindex: 0,
+                        onclick: move |_| despawn(false),
+                        "Delete"
+                    }
+                    view {
+                        class: "py-4 hover:text-white",
+                        tabindex: 0,
+                        onclick: move |_| despawn(true),
+                        "Delete with children"
+                    }
+                }
             }
         }
 
@@ -117,7 +319,7 @@ fn Entity<'a>(cx: Scope, entity: &'a Entity, components: Vec<RonComponent>) -> E
 }
 
 #[component]
-fn Component<'a>(cx: Scope, component: &'a RonComponent) -> Element {
+fn Component<'a>(cx: Scope, component: &'a ComponentData) -> Element {
     render! {
         view {
             class: "text-white flex-col text-14 p-4",
@@ -0,0 +1,133 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::ecs::entity::Entity;
+use roth_shared::{ComponentData, RuntimeToEditorMsg};
+
+/// The editor's copy of the runtime's entities. Filled by the answer to `GetEntities` and kept up
+/// to date by applying the `EntitiesChanged` diffs the runtime sends afterwards.
+#[derive(Debug, Default)]
+pub struct WorldModel {
+    entities: BTreeMap<Entity, Vec<ComponentData>>,
+    /// children of every entity that has any, in order
+    children: HashMap<Entity, Vec<Entity>>,
+    parents: HashMap<Entity, Entity>,
+}
+
+impl WorldModel {
+    /// Applies `msg` if it describes entities, returns whether anything was applied.
+    pub fn apply(&mut self, msg: &RuntimeToEditorMsg) -> bool {
+        match msg {
+            RuntimeToEditorMsg::Entities {
+                entities,
+                hierarchy,
+            } => {
+                self.entities = entities.iter().cloned().collect();
+                self.children.clear();
+                self.parents.clear();
+                for (parent, children) in hierarchy {
+                    self.set_children(*parent, children.clone());
+                }
+                true
+            }
+            RuntimeToEditorMsg::EntitiesChanged {
+                spawned,
+                despawned,
+                components_changed,
+                components_removed,
+                hierarchy_changed,
+            } => {
+                for (entity, components) in spawned {
+                    self.entities.insert(*entity, components.clone());
+                }
+
+                for entity in despawned {
+                    self.entities.remove(entity);
+                    self.set_children(*entity, vec![]);
+                    if let Some(parent) = self.parents.remove(entity) {
+                        if let Some(siblings) = self.children.get_mut(&parent) {
+                            siblings.retain(|it| it != entity);
+                        }
+                    }
+                }
+
+                for (entity, changed) in components_changed {
+                    let components = self.entities.entry(*entity).or_default();
+                    for component in changed {
+                        match components
+                            .iter_mut()
+                            .find(|it| it.type_name == component.type_name)
+                        {
+                            Some(existing) => *existing = component.clone(),
+                            None => components.push(component.clone()),
+                        }
+                    }
+                }
+
+                for (entity, removed) in components_removed {
+                    if let Some(components) = self.entities.get_mut(entity) {
+                        components.retain(|it| !removed.contains(&it.type_name));
+                    }
+                }
+
+                for (parent, children) in hierarchy_changed {
+                    self.set_children(*parent, children.clone());
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_children(&mut self, parent: Entity, children: Vec<Entity>) {
+        for child in self.children.remove(&parent).unwrap_or_default() {
+            if self.parents.get(&child) == Some(&parent) {
+                self.parents.remove(&child);
+            }
+        }
+        if children.is_empty() {
+            return;
+        }
+        for child in &children {
+            self.parents.insert(*child, parent);
+        }
+        self.children.insert(parent, children);
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.children.clear();
+        self.parents.clear();
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains_key(&entity)
+    }
+
+    pub fn components(&self, entity: Entity) -> Option<&[ComponentData]> {
+        self.entities.get(&entity).map(|it| it.as_slice())
+    }
+
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.children
+            .get(&entity)
+            .map(|it| it.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.parents.get(&entity).copied()
+    }
+
+    /// The entities without a parent, in the order of their ids.
+    pub fn roots(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities
+            .keys()
+            .copied()
+            .filter(|entity| !self.parents.contains_key(entity))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Entity, &Vec<ComponentData>)> {
+        self.entities.iter()
+    }
+}